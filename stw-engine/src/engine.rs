@@ -0,0 +1,90 @@
+use serde_json::{json, Value};
+use stw_game_lib::{game::{STWGame, replay::Replay}, resource::load_resources_from_dir, GameConfig};
+
+use crate::protocol::{Command, ok, bad_move, error};
+
+#[derive(Default)]
+pub struct Engine{
+    game: Option<STWGame>
+}
+
+impl Engine {
+
+    /// Handles one command and returns the response line.
+    pub fn handle(&mut self, command: Command) -> Value{
+        match command {
+            Command::NewGame { resources, seed, size, name } => self.new_game(&resources, seed, size, name),
+            Command::PerformMove { game_move } => match &mut self.game {
+                Some(game) => match game.perform_move(&game_move) {
+                    Ok(callbacks) => ok(json!({"callbacks": callbacks})),
+                    Err(e) => bad_move(&e.msg),
+                },
+                None => no_game(),
+            },
+            Command::LegalMoves => match &self.game {
+                Some(game) => ok(json!({"moves": game.get_possible_moves()})),
+                None => no_game(),
+            },
+            Command::State => match &self.game {
+                Some(game) => ok(json!({"state": game.get_state()})),
+                None => no_game(),
+            },
+            Command::Save { path } => match &self.game {
+                Some(game) => match game.get_replay().save_to_file(&path) {
+                    Ok(()) => ok(json!({})),
+                    Err(e) => error(&e.msg),
+                },
+                None => no_game(),
+            },
+            Command::Load { path, resources } => self.load(&path, &resources),
+            Command::Quit => ok(json!({})),
+        }
+    }
+
+    fn new_game(&mut self, resources: &str, seed: Option<String>, size: Option<u32>, name: Option<String>) -> Value{
+        let resources = match load_resources_from_dir(resources) {
+            Ok(resources) => resources,
+            Err(e) => return error(&e.msg),
+        };
+        let mut config = GameConfig::new().set_resources(resources);
+        if let Some(seed) = seed {
+            config = config.set_seed(&seed);
+        }
+        if let Some(size) = size {
+            config = config.set_map_size(size);
+        }
+        if let Some(name) = name {
+            config = config.set_name(&name);
+        }
+        match config.start_game() {
+            Ok(game) => {
+                let seed = game.get_replay().seed.to_string();
+                self.game = Some(game);
+                ok(json!({"seed": seed}))
+            },
+            Err(e) => error(&e.msg),
+        }
+    }
+
+    fn load(&mut self, path: &str, resources: &str) -> Value{
+        let replay = match Replay::load_from_file(path) {
+            Ok(replay) => replay,
+            Err(e) => return error(&e.msg),
+        };
+        let resources = match load_resources_from_dir(resources) {
+            Ok(resources) => resources,
+            Err(e) => return error(&e.msg),
+        };
+        match replay.play(resources) {
+            Ok(game) => {
+                self.game = Some(game);
+                ok(json!({"seed": replay.seed}))
+            },
+            Err(e) => error(&e.to_string()),
+        }
+    }
+}
+
+fn no_game() -> Value{
+    error("No game - send new_game or load first")
+}
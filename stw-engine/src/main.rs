@@ -0,0 +1,34 @@
+mod engine;
+mod protocol;
+
+use std::io::{self, BufRead, Write};
+
+use engine::Engine;
+use protocol::{Command, error};
+
+fn main() {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout().lock();
+    let mut engine = Engine::default();
+
+    for line in stdin.lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (response, quit) = match serde_json::from_str::<Command>(&line) {
+            Ok(command) => {
+                let quit = matches!(command, Command::Quit);
+                (engine.handle(command), quit)
+            },
+            Err(e) => (error(&format!("Can not parse command: {}", e)), false),
+        };
+        if writeln!(stdout, "{}", response).and_then(|_|stdout.flush()).is_err() || quit {
+            break;
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+use stw_game_lib::game::game_move::GameMove;
+
+/// One line of input. See `docs/engine-protocol.md`.
+#[derive(Deserialize, Debug)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum Command{
+    NewGame{resources: String, seed: Option<String>, size: Option<u32>, name: Option<String>},
+    PerformMove{#[serde(rename = "move")] game_move: GameMove},
+    LegalMoves,
+    State,
+    Save{path: String},
+    Load{path: String, resources: String},
+    Quit
+}
+
+pub fn ok(payload: Value) -> Value{
+    let mut res = json!({"status": "ok"});
+    if let (Some(res), Value::Object(payload)) = (res.as_object_mut(), payload) {
+        res.extend(payload);
+    }
+    res
+}
+
+pub fn bad_move(msg: &str) -> Value{
+    json!({"status": "bad_move", "msg": msg})
+}
+
+pub fn error(msg: &str) -> Value{
+    json!({"status": "error", "msg": msg})
+}
@@ -0,0 +1,104 @@
+use std::{io::{BufRead, BufReader, Write}, process::{Command, Stdio, ChildStdin, ChildStdout}};
+
+use serde_json::{json, Value};
+
+const RESOURCES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../stw-game-lib/res");
+
+struct EngineProcess{
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>
+}
+
+impl EngineProcess {
+    fn send(&mut self, command: Value) -> Value{
+        writeln!(self.stdin, "{}", command).unwrap();
+        self.stdin.flush().unwrap();
+        let mut line = String::new();
+        self.stdout.read_line(&mut line).unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
+}
+
+fn start_engine() -> (std::process::Child, EngineProcess){
+    let mut child = Command::new(env!("CARGO_BIN_EXE_stw-engine"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let process = EngineProcess {
+        stdin: child.stdin.take().unwrap(),
+        stdout: BufReader::new(child.stdout.take().unwrap())
+    };
+    (child, process)
+}
+
+#[test]
+fn engine_plays_through_pipes() {
+    let (mut child, mut engine) = start_engine();
+
+    let res = engine.send(json!({"cmd": "legal_moves"}));
+    assert_eq!(res["status"], "error");
+
+    let res = engine.send(json!({"cmd": "new_game", "resources": RESOURCES, "seed": "test seed", "size": 37}));
+    assert_eq!(res, json!({"status": "ok", "seed": "test seed"}));
+
+    // a bad config keeps the running game
+    let state = engine.send(json!({"cmd": "state"}));
+    let res = engine.send(json!({"cmd": "new_game", "resources": RESOURCES, "size": 0}));
+    assert_eq!(res["status"], "error");
+    assert_eq!(engine.send(json!({"cmd": "state"})), state);
+
+    let res = engine.send(json!({"cmd": "legal_moves"}));
+    let moves = res["moves"].as_array().unwrap();
+    assert!(moves.contains(&json!({"type": "Build", "data": [{"q": 8, "r": 18}, "cottage"]})));
+    assert!(moves.contains(&json!({"type": "Wait"})));
+
+    let res = engine.send(json!({"cmd": "perform_move", "move": {"type": "Build", "data": [{"q": 8, "r": 18}, "cottage"]}}));
+    assert_eq!(res["status"], "ok");
+    assert!(res["callbacks"].as_array().unwrap().contains(&json!({"type": "ChangedResource", "data": {"resource": "Gold", "new_value": 3}})));
+
+    let res = engine.send(json!({"cmd": "perform_move", "move": {"type": "Build", "data": [{"q": 9, "r": 18}, "village"]}}));
+    assert_eq!(res["status"], "bad_move");
+
+    let res = engine.send(json!({"cmd": "perform_move", "move": {"type": "Wait"}}));
+    assert_eq!(res["status"], "ok");
+
+    let res = engine.send(json!({"cmd": "perform_move", "move": {"type": "RenameHero", "data": [0, "Jan"]}}));
+    assert_eq!(res["callbacks"], json!([{"type": "HeroRenamed", "data": {"hero_number": 0, "name": "Jan"}}]));
+
+    let res = engine.send(json!({"cmd": "state"}));
+    assert_eq!(res["state"]["turn"], 1);
+    assert_eq!(res["state"]["resources"]["Gold"], 3);
+    assert_eq!(res["state"]["heroes"].as_array().unwrap().len(), 1);
+
+    let res = engine.send(json!({"cmd": "nonsense"}));
+    assert_eq!(res["status"], "error");
+
+    let res = engine.send(json!({"cmd": "quit"}));
+    assert_eq!(res["status"], "ok");
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn engine_saves_and_loads() {
+    let save_path = std::env::temp_dir().join(format!("stw-engine-test-{}.json", std::process::id()));
+    let save_path = save_path.to_str().unwrap();
+    let (mut child, mut engine) = start_engine();
+
+    engine.send(json!({"cmd": "new_game", "resources": RESOURCES, "seed": "save seed", "size": 37}));
+    engine.send(json!({"cmd": "perform_move", "move": {"type": "Build", "data": [{"q": 8, "r": 18}, "cottage"]}}));
+    engine.send(json!({"cmd": "perform_move", "move": {"type": "Wait"}}));
+    let state = engine.send(json!({"cmd": "state"}));
+
+    assert_eq!(engine.send(json!({"cmd": "save", "path": save_path}))["status"], "ok");
+    assert_eq!(engine.send(json!({"cmd": "new_game", "resources": RESOURCES, "size": 37}))["status"], "ok");
+    assert_ne!(engine.send(json!({"cmd": "state"})), state);
+
+    let res = engine.send(json!({"cmd": "load", "path": save_path, "resources": RESOURCES}));
+    assert_eq!(res, json!({"status": "ok", "seed": "save seed"}));
+    assert_eq!(engine.send(json!({"cmd": "state"})), state);
+
+    drop(engine);
+    assert!(child.wait().unwrap().success());
+    std::fs::remove_file(save_path).unwrap();
+}
@@ -0,0 +1,4 @@
+//! Builds the C API of `stw_game_lib::ffi` as a shared library, so the game
+//! library itself stays an rlib.
+
+pub use stw_game_lib::ffi::*;
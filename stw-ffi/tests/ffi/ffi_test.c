@@ -0,0 +1,68 @@
+/* Plays a few moves through the C API. Usage: ffi_test RESOURCES_JSON SAVE_PATH */
+#include <assert.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "stw_game.h"
+
+static char *read_file(const char *path)
+{
+    FILE *file = fopen(path, "rb");
+    assert(file);
+    fseek(file, 0, SEEK_END);
+    long size = ftell(file);
+    fseek(file, 0, SEEK_SET);
+    char *text = malloc(size + 1);
+    size_t read = fread(text, 1, size, file);
+    assert(read == (size_t)size);
+    text[size] = '\0';
+    fclose(file);
+    return text;
+}
+
+int main(int argc, char **argv)
+{
+    assert(argc == 3);
+    char *resources = read_file(argv[1]);
+
+    assert(stw_game_new("not json", NULL, 0) == NULL);
+    assert(strlen(stw_last_error()) > 0);
+
+    STWGame *game = stw_game_new(resources, "test seed", 37);
+    assert(game);
+
+    char *moves = stw_game_legal_moves(game);
+    assert(strstr(moves, "\"Wait\""));
+    stw_string_free(moves);
+
+    char *callbacks = stw_game_perform_move(game, "{\"type\":\"Build\",\"data\":[{\"q\":8,\"r\":18},\"cottage\"]}");
+    assert(strstr(callbacks, "NewTileContent"));
+    stw_string_free(callbacks);
+
+    char *bad_move = stw_game_perform_move(game, "{\"type\":\"Build\",\"data\":[{\"q\":8,\"r\":18},\"cottage\"]}");
+    assert(strstr(bad_move, "bad_move"));
+    stw_string_free(bad_move);
+
+    assert(stw_game_perform_move(game, "{\"type\":\"Fly\"}") == NULL);
+
+    char *tile = stw_game_tile(game, 8, 18);
+    assert(strstr(tile, "\"content\":\"cottage\""));
+    stw_string_free(tile);
+    assert(stw_game_tile(game, -100, -100) == NULL);
+
+    assert(stw_game_save(game, argv[2]));
+    STWGame *loaded = stw_game_load(argv[2], resources);
+    assert(loaded);
+
+    char *state = stw_game_state(game);
+    char *loaded_state = stw_game_state(loaded);
+    assert(strcmp(state, loaded_state) == 0);
+    stw_string_free(state);
+    stw_string_free(loaded_state);
+
+    stw_game_free(loaded);
+    stw_game_free(game);
+    free(resources);
+    puts("ok");
+    return 0;
+}
@@ -0,0 +1,46 @@
+use std::{env, fs, path::PathBuf, process::Command};
+
+use serde_json::json;
+use stw_game_lib::resource::load_resources_from_dir;
+
+/// Compiles `tests/ffi/ffi_test.c` against the cdylib and runs it.
+#[test]
+fn test_c_program() {
+    let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let game_lib = manifest.join("../stw-game-lib");
+    // target/<profile>/deps/ffi_test-<hash>, the cdylib next to it is always
+    // the one built for this test (the copy in target/<profile> may be stale)
+    let lib_dir = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    let work_dir = env::temp_dir().join(format!("stw-ffi-{}", std::process::id()));
+    fs::create_dir_all(&work_dir).unwrap();
+
+    let resources: Vec<_> = load_resources_from_dir(game_lib.join("res").to_str().unwrap()).unwrap()
+        .into_iter()
+        .map(|e|json!({"type": e.0, "resource": e.1}))
+        .collect();
+    let resources_path = work_dir.join("resources.json");
+    fs::write(&resources_path, json!(resources).to_string()).unwrap();
+
+    let program = work_dir.join("ffi_test");
+    let status = Command::new(env::var("CC").unwrap_or("cc".to_string()))
+        .arg(manifest.join("tests/ffi/ffi_test.c"))
+        .arg("-I").arg(game_lib.join("include"))
+        .arg("-L").arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-lstw_game")
+        .arg("-o").arg(&program)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let output = Command::new(&program)
+        .arg(&resources_path)
+        .arg(work_dir.join("save.json"))
+        // cargo puts target/<profile> on the library path, ahead of the rpath
+        .env("LD_LIBRARY_PATH", &lib_dir)
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&work_dir).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "ok\n");
+}
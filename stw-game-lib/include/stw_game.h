@@ -0,0 +1,90 @@
+#ifndef STW_GAME_H
+#define STW_GAME_H
+
+/* Generated by cbindgen from src/ffi.rs, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef struct STWGame STWGame;
+
+/**
+ * Message of the last failed call on this thread, empty if there was none.
+ */
+const char *stw_last_error(void);
+
+/**
+ * Starts a new game. `resources` is a json array of `{"type", "resource"}`
+ * entries (the content of all files from `res/` merged). `seed` may be
+ * `NULL` for a random seed and `map_size` 0 for the default size.
+ *
+ * # Safety
+ * `resources` and `seed` have to be `NULL` or valid C strings.
+ */
+STWGame *stw_game_new(const char *resources, const char *seed, uint32_t map_size);
+
+/**
+ * Rebuilds a game saved with `stw_game_save`.
+ *
+ * # Safety
+ * `path` and `resources` have to be valid C strings.
+ */
+STWGame *stw_game_load(const char *path, const char *resources);
+
+/**
+ * Writes the game to `path`. Returns `false` on failure.
+ *
+ * # Safety
+ * `game` has to come from this library, `path` has to be a valid C string.
+ */
+bool stw_game_save(STWGame *game, const char *path);
+
+/**
+ * # Safety
+ * `game` has to be `NULL` or come from this library, and can not be used
+ * afterwards.
+ */
+void stw_game_free(STWGame *game);
+
+/**
+ * # Safety
+ * `text` has to be `NULL` or a string returned by this library.
+ */
+void stw_string_free(char *text);
+
+/**
+ * Performs a json encoded `GameMove`. Returns `{"callbacks": [...]}` or
+ * `{"bad_move": {"msg": ...}}` when the move is not allowed.
+ *
+ * # Safety
+ * `game` has to come from this library, `game_move` has to be a valid C string.
+ */
+char *stw_game_perform_move(STWGame *game, const char *game_move);
+
+/**
+ * Json array of every move allowed right now.
+ *
+ * # Safety
+ * `game` has to come from this library.
+ */
+char *stw_game_legal_moves(STWGame *game);
+
+/**
+ * Json `GameState` of the whole game.
+ *
+ * # Safety
+ * `game` has to come from this library.
+ */
+char *stw_game_state(STWGame *game);
+
+/**
+ * Json `TileState` of the tile at `(q, r)`, `NULL` outside of the map.
+ *
+ * # Safety
+ * `game` has to come from this library.
+ */
+char *stw_game_tile(STWGame *game, int32_t q, int32_t r);
+
+#endif  /* STW_GAME_H */
@@ -0,0 +1,52 @@
+import pickle
+import unittest
+
+import stw
+
+
+def start_game():
+    return stw.GameConfig().set_seed("test seed").set_map_size(37).start_game()
+
+
+class GameTest(unittest.TestCase):
+    def test_start(self):
+        game = start_game()
+        self.assertEqual(game.turn, 0)
+        self.assertEqual(game.tile(9, 18).content, "village_small")
+        self.assertIsNone(game.tile(-100, -100))
+        self.assertEqual(len(game.heroes()), 0)
+        self.assertIn(stw.GameMove.wait(), game.legal_moves())
+
+    def test_step(self):
+        game = start_game()
+        self.assertEqual(game.possible_buildings(8, 18)[0], ("cottage", False))
+        callbacks = game.step(stw.GameMove.build(8, 18, "cottage"))
+        new_content = [e for e in callbacks if e.type == "NewTileContent"]
+        self.assertEqual(new_content[0].data, {"position": {"q": 8, "r": 18}, "field_type_id": "cottage"})
+        self.assertEqual(game.resources["Gold"], 3)
+        self.assertEqual(game.tile(8, 18).content, "cottage")
+
+        with self.assertRaises(stw.BadMoveError):
+            game.step(stw.GameMove.build(8, 18, "cottage"))
+
+    def test_move_dict(self):
+        game_move = stw.GameMove.build(8, 18, "cottage")
+        self.assertEqual(game_move.to_dict(), {"type": "Build", "data": [{"q": 8, "r": 18}, "cottage"]})
+        self.assertEqual(stw.GameMove.from_dict(game_move.to_dict()), game_move)
+
+    def test_pickle(self):
+        game = start_game()
+        game.step(stw.GameMove.build(8, 18, "cottage"))
+        game.step(stw.GameMove.wait())
+
+        copy = pickle.loads(pickle.dumps(game))
+        self.assertEqual(copy.state(), game.state())
+        self.assertEqual(copy.step(stw.GameMove.wait()), game.step(stw.GameMove.wait()))
+
+    def test_load_other_resources(self):
+        with self.assertRaises(stw.StwError):
+            stw.STWGame.load(start_game().save(), "[]")
+
+
+if __name__ == "__main__":
+    unittest.main()
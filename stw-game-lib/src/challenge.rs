@@ -0,0 +1,168 @@
+//! Daily challenges: every player gets the same map and spawns for a date.
+//! Scores go to a local leaderboard file, each with the replay it came from,
+//! and are only accepted after the game is simulated again.
+
+use std::{collections::BTreeMap, fs, path::{Path, PathBuf}};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{game::{STWGame, GameError, game_move::GameMove, replay::Replay, summary::GameSummary}, resource::resources_hash, GameConfig};
+
+/// Map size of every challenge.
+pub const CHALLENGE_MAP_SIZE: u32 = 37;
+
+/// Directory with the replays, next to the leaderboard file.
+const REPLAY_DIR: &str = "replays";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Challenge{
+    /// Date and resource set, e.g. `daily-2024-05-01-0123abcd`.
+    pub id: String,
+    pub seed: String,
+    pub map_size: u32,
+    pub resources_hash: String
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LeaderboardEntry{
+    pub player: String,
+    pub score: u32,
+    pub turns: u32,
+    /// Replay file, relative to the leaderboard file.
+    pub replay: String
+}
+
+/// Scores of every challenge, best first, stored as one json file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leaderboard{
+    path: PathBuf,
+    entries: BTreeMap<String, Vec<LeaderboardEntry>>
+}
+
+fn check_date(date: &str) -> Result<(), GameError>{
+    let parts: Vec<&str> = date.split('-').collect();
+    let valid = parts.len() == 3
+        && [4, 2, 2].iter().zip(parts.iter()).all(|(len, e)|e.len() == *len && e.chars().all(|c|c.is_ascii_digit()))
+        && (1..=12).contains(&parts[1].parse::<u32>().unwrap_or(0))
+        && (1..=31).contains(&parts[2].parse::<u32>().unwrap_or(0));
+    if valid {
+        Ok(())
+    } else {
+        Err(GameError::new(format!("Date {} is not in YYYY-MM-DD format", date)))
+    }
+}
+
+impl Challenge {
+    /// Challenge of the day `date` (`YYYY-MM-DD`) on the given resources.
+    pub fn daily(date: &str, resources: &[(String, serde_json::Value)]) -> Result<Challenge, GameError>{
+        check_date(date)?;
+        let hash = resources_hash(resources);
+        Ok(Challenge {
+            id: format!("daily-{}-{}", date, &hash[..8]),
+            seed: format!("daily-{}", date),
+            map_size: CHALLENGE_MAP_SIZE,
+            resources_hash: hash
+        })
+    }
+
+    pub fn start_game(&self, resources: Vec<(String, serde_json::Value)>) -> Result<STWGame, GameError>{
+        if resources_hash(&resources) != self.resources_hash {
+            return Err(GameError::new(format!("Resources differ from the ones of challenge {}", self.id)));
+        }
+        GameConfig::new()
+            .set_name(&self.id)
+            .set_seed(&self.seed)
+            .set_map_size(self.map_size)
+            .set_resources(resources)
+            .start_game()
+    }
+
+    /// Plays `moves` on a new challenge game and checks that it ends with
+    /// `score`. Fails on the first rejected move.
+    pub fn verify(&self, moves: &[GameMove], score: u32, resources: Vec<(String, serde_json::Value)>) -> Result<GameSummary, GameError>{
+        let mut game = self.start_game(resources)?;
+        for (i, game_move) in moves.iter().enumerate() {
+            game.perform_move(game_move)
+                .map_err(|e|GameError::new(format!("Move {} ({:?}) is rejected: {}", i, game_move, e.msg)))?;
+        }
+        let summary = game.get_summary();
+        if summary.score != score {
+            return Err(GameError::new(format!("Claimed score {} but the moves give {}", score, summary.score)));
+        }
+        Ok(summary)
+    }
+
+    /// `verify` for a recorded game, which also has to be recorded on this
+    /// challenge.
+    pub fn verify_replay(&self, replay: &Replay, score: u32, resources: Vec<(String, serde_json::Value)>) -> Result<GameSummary, GameError>{
+        if replay.seed != self.seed || replay.map_size != self.map_size || replay.resources_hash != self.resources_hash {
+            return Err(GameError::new(format!("Replay was not recorded on challenge {}", self.id)));
+        }
+        let moves: Vec<GameMove> = replay.steps.iter().map(|e|e.game_move.clone()).collect();
+        self.verify(&moves, score, resources)
+    }
+}
+
+impl Leaderboard {
+    /// Leaderboard stored at `path`, empty when the file does not exist yet.
+    pub fn load(path: &Path) -> Result<Leaderboard, GameError>{
+        let entries = if path.exists() {
+            let text = fs::read_to_string(path)
+                .map_err(|e|GameError::new(format!("Can not read leaderboard {}: {}", path.display(), e)))?;
+            serde_json::from_str(&text)
+                .map_err(|e|GameError::new(format!("Can not parse leaderboard {}: {}", path.display(), e)))?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Leaderboard { path: path.to_path_buf(), entries })
+    }
+
+    pub fn save(&self) -> Result<(), GameError>{
+        let text = serde_json::to_string_pretty(&self.entries)
+            .map_err(|e|GameError::new(format!("Can not serialize leaderboard: {}", e)))?;
+        fs::write(&self.path, text)
+            .map_err(|e|GameError::new(format!("Can not write leaderboard {}: {}", self.path.display(), e)))
+    }
+
+    /// Entries of the challenge, best first.
+    pub fn get_entries(&self, challenge_id: &str) -> &[LeaderboardEntry]{
+        self.entries.get(challenge_id).map_or(&[], |e|e.as_slice())
+    }
+
+    /// Verifies the replay, stores it next to the leaderboard and records the
+    /// score. The leaderboard file is saved too.
+    pub fn submit(&mut self, challenge: &Challenge, player: &str, replay: &Replay, score: u32, resources: Vec<(String, serde_json::Value)>) -> Result<LeaderboardEntry, GameError>{
+        let summary = challenge.verify_replay(replay, score, resources)?;
+
+        let dir = self.path.parent().unwrap_or(Path::new(".")).join(REPLAY_DIR);
+        fs::create_dir_all(&dir)
+            .map_err(|e|GameError::new(format!("Can not create replay directory {}: {}", dir.display(), e)))?;
+        let entries = self.entries.entry(challenge.id.to_string()).or_default();
+        let file = format!("{}-{}.json", challenge.id, entries.len());
+        replay.save_to_file(&dir.join(&file).to_string_lossy())?;
+
+        let entry = LeaderboardEntry {
+            player: player.to_string(),
+            score: summary.score,
+            turns: summary.turns,
+            replay: format!("{}/{}", REPLAY_DIR, file)
+        };
+        entries.push(entry.clone());
+        // stable, so equal scores keep the order of submission
+        entries.sort_by_key(|e|std::cmp::Reverse(e.score));
+        self.save()?;
+        Ok(entry)
+    }
+
+    /// Checks every entry of the challenge against its replay file and
+    /// returns the players whose score does not match.
+    pub fn verify_entries(&self, challenge: &Challenge, resources: Vec<(String, serde_json::Value)>) -> Vec<String>{
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        self.get_entries(&challenge.id).iter()
+            .filter(|e|Replay::load_from_file(&dir.join(&e.replay).to_string_lossy())
+                .and_then(|replay|challenge.verify_replay(&replay, e.score, resources.clone()))
+                .is_err())
+            .map(|e|e.player.to_string())
+            .collect()
+    }
+}
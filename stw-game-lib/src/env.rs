@@ -0,0 +1,317 @@
+//! Gym-style environment for training agents against the real rules.
+//!
+//! Observations have a fixed shape for a given map size and resource set:
+//! `TILE_FEATURES` numbers per tile (in `TilePos` order) plus a vector of
+//! global values. Actions are indices into a flat space - `Wait`, then every
+//! `Build`, `StartHistory`, `PlayMove` and `MakeDecision` combination - and
+//! `Observation::action_mask` says which of them are legal right now.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use strum::IntoEnumIterator;
+
+use crate::{
+    game::{STWGame, GameError, map::TilePos, game_move::GameMove, game_controller::GameCallback},
+    resource::{enums::{GResource, HeroSkill}, resource_data::{OriginFieldData, FieldTypeData, QuestData, ActionData}},
+    GameConfig
+};
+
+/// Origin index, content index, visibility, quest presence. Indices start
+/// from 1, 0 means no content or a tile that is not visible.
+pub const TILE_FEATURES: usize = 4;
+
+/// How a step is scored. Every field is added up for each matching event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RewardConfig{
+    /// Reward per unit of resource gained (negative when spent).
+    pub resources: BTreeMap<GResource, f32>,
+    pub new_building: f32,
+    pub new_hero: f32,
+    pub quest_success: f32,
+    pub quest_failure: f32,
+    pub hero_died: f32,
+    /// Added for every passed turn.
+    pub turn: f32,
+    /// Given for a legal-looking index the game rejects.
+    pub illegal_action: f32
+}
+
+impl Default for RewardConfig {
+    fn default() -> Self {
+        RewardConfig {
+            resources: BTreeMap::from([(GResource::Gold, 0.1)]),
+            new_building: 1.0,
+            new_hero: 1.0,
+            quest_success: 5.0,
+            quest_failure: -2.0,
+            hero_died: -5.0,
+            turn: 0.0,
+            illegal_action: -1.0
+        }
+    }
+}
+
+pub struct EnvConfig{
+    resources: Vec<(String, serde_json::Value)>,
+    map_size: Option<u32>,
+    max_heroes: usize,
+    max_turns: u32,
+    reward: RewardConfig
+}
+
+impl EnvConfig {
+    pub fn new() -> EnvConfig{
+        EnvConfig { resources: vec![], map_size: None, max_heroes: 4, max_turns: 200, reward: RewardConfig::default() }
+    }
+
+    pub fn set_resources(mut self, resources: Vec<(String, serde_json::Value)>) -> Self{
+        self.resources = resources;
+        self
+    }
+
+    pub fn set_map_size(mut self, map_size: u32) -> Self{
+        self.map_size = Some(map_size);
+        self
+    }
+
+    /// Number of hero slots in observations and in the `StartHistory` actions.
+    pub fn set_max_heroes(mut self, max_heroes: usize) -> Self{
+        self.max_heroes = max_heroes;
+        self
+    }
+
+    /// Episode ends after this many turns.
+    pub fn set_max_turns(mut self, max_turns: u32) -> Self{
+        self.max_turns = max_turns;
+        self
+    }
+
+    pub fn set_reward(mut self, reward: RewardConfig) -> Self{
+        self.reward = reward;
+        self
+    }
+}
+
+impl Default for EnvConfig {
+    fn default() -> Self {
+        EnvConfig::new()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation{
+    /// `tile_count * TILE_FEATURES` values, row per tile.
+    pub tiles: Vec<f32>,
+    /// Resources, turn, max heroes, hero count, path left in the current
+    /// history (0 without one), then skills of every hero slot.
+    pub globals: Vec<f32>,
+    pub action_mask: Vec<bool>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepResult{
+    pub observation: Observation,
+    pub reward: f32,
+    pub done: bool,
+    pub callbacks: Vec<GameCallback>
+}
+
+pub struct STWEnv{
+    config: EnvConfig,
+    game: STWGame,
+
+    positions: Vec<TilePos>,
+    position_index: HashMap<TilePos, usize>,
+    origins: Vec<String>,
+    contents: Vec<String>,
+    actions: Vec<String>,
+    decisions: Vec<String>
+}
+
+fn index_of(ids: &[String], id: &str) -> Option<usize>{
+    ids.binary_search_by(|e|e.as_str().cmp(id)).ok()
+}
+
+impl STWEnv {
+    /// Creates the environment with a first episode started from `seed`.
+    pub fn new(config: EnvConfig, seed: &str) -> Result<STWEnv, GameError>{
+        let game = STWEnv::start_game(&config, seed)?;
+        let rm = game.get_resource_manager();
+
+        let sorted_ids = |ids: Vec<&str>|{
+            let set: BTreeSet<String> = ids.into_iter().map(|e|e.to_string()).collect();
+            set.into_iter().collect::<Vec<_>>()
+        };
+        let origin_data = rm.get_resources::<OriginFieldData>();
+        let content_data = rm.get_resources::<FieldTypeData>();
+        let origins = sorted_ids(origin_data.keys().copied().collect());
+        let contents = sorted_ids(content_data.keys().copied().collect());
+        // hero moves may use actions missing from ActionData and "" for just walking through
+        let actions = sorted_ids(rm.get_resources::<ActionData>().keys().copied()
+            .chain(origin_data.values().flat_map(|e|e.data.possible_actions.iter().map(|a|a.as_str())))
+            .chain(content_data.values().flat_map(|e|e.data.possible_actions.iter().map(|a|a.as_str())))
+            .chain(std::iter::once(""))
+            .collect());
+        let decisions = sorted_ids(rm.get_resources::<QuestData>().values()
+            .flat_map(|e|e.data.quest_decisions.iter().map(|d|d.id.as_str()))
+            .collect());
+
+        let positions: Vec<TilePos> = game.get_map().iter_sorted().into_iter().map(|e|*e.0).collect();
+        let position_index = positions.iter().enumerate().map(|e|(*e.1, e.0)).collect();
+        Ok(STWEnv { config, game, positions, position_index, origins, contents, actions, decisions })
+    }
+
+    fn start_game(config: &EnvConfig, seed: &str) -> Result<STWGame, GameError>{
+        let mut game_config = GameConfig::new()
+            .set_resources(config.resources.clone())
+            .set_seed(seed);
+        if let Some(map_size) = config.map_size {
+            game_config = game_config.set_map_size(map_size);
+        }
+        game_config.start_game()
+    }
+
+    pub fn reset(&mut self, seed: &str) -> Result<Observation, GameError>{
+        self.game = STWEnv::start_game(&self.config, seed)?;
+        Ok(self.observe())
+    }
+
+    /// Performs the action with the given index. A rejected action leaves the
+    /// game unchanged and is scored with `RewardConfig::illegal_action`.
+    pub fn step(&mut self, action_index: usize) -> Result<StepResult, GameError>{
+        let game_move = self.decode_action(action_index)
+            .ok_or_else(||GameError::new(format!("Action {} is out of range {}", action_index, self.action_count())))?;
+        let resources_before = self.game.get_resources().clone();
+        let turn_before = self.game.get_turn();
+
+        let (reward, callbacks) = match self.game.perform_move(&game_move) {
+            Ok(callbacks) => (self.reward(&resources_before, turn_before, &callbacks), callbacks),
+            Err(_) => (self.config.reward.illegal_action, vec![]),
+        };
+        let observation = self.observe();
+        let done = self.game.is_game_over() || self.game.get_turn() >= self.config.max_turns || !observation.action_mask.contains(&true);
+        Ok(StepResult { observation, reward, done, callbacks })
+    }
+
+    pub fn get_game(&self) -> &STWGame{
+        &self.game
+    }
+
+    pub fn tile_count(&self) -> usize{
+        self.positions.len()
+    }
+
+    pub fn globals_len(&self) -> usize{
+        GResource::iter().count() + 4 + self.config.max_heroes * HeroSkill::iter().count()
+    }
+
+    pub fn action_count(&self) -> usize{
+        1 + self.positions.len() * (self.contents.len() + self.config.max_heroes + self.actions.len()) + self.decisions.len()
+    }
+
+    /// Index of `game_move` in the action space, `None` for moves outside it.
+    /// `RenameHero` does not change the game, `Trade` takes a free amount and
+    /// `CancelConstruction` and `Demolish` only undo a `Build`, so none of
+    /// them has an index and agents can not play them.
+    pub fn encode_action(&self, game_move: &GameMove) -> Option<usize>{
+        let tiles = self.positions.len();
+        let build_start = 1;
+        let history_start = build_start + tiles * self.contents.len();
+        let play_start = history_start + tiles * self.config.max_heroes;
+        let decision_start = play_start + tiles * self.actions.len();
+        match game_move {
+            GameMove::Wait => Some(0),
+            GameMove::Build(pos, id) =>
+                Some(build_start + self.position_index.get(pos)? * self.contents.len() + index_of(&self.contents, id)?),
+            GameMove::StartHistory(pos, hero) if *hero < self.config.max_heroes =>
+                Some(history_start + self.position_index.get(pos)? * self.config.max_heroes + hero),
+            GameMove::PlayMove(pos, action) =>
+                Some(play_start + self.position_index.get(pos)? * self.actions.len() + index_of(&self.actions, action)?),
+            GameMove::MakeDecision(decision) => Some(decision_start + index_of(&self.decisions, decision)?),
+            _ => None,
+        }
+    }
+
+    pub fn decode_action(&self, index: usize) -> Option<GameMove>{
+        if index == 0 {
+            return Some(GameMove::Wait);
+        }
+        let mut index = index - 1;
+        let tiles = self.positions.len();
+        for (width, kind) in [(self.contents.len(), 0), (self.config.max_heroes, 1), (self.actions.len(), 2)] {
+            if index < tiles * width {
+                let pos = self.positions[index / width];
+                let item = index % width;
+                return Some(match kind {
+                    0 => GameMove::Build(pos, self.contents[item].to_string()),
+                    1 => GameMove::StartHistory(pos, item),
+                    _ => GameMove::PlayMove(pos, self.actions[item].to_string()),
+                });
+            }
+            index -= tiles * width;
+        }
+        self.decisions.get(index).map(|e|GameMove::MakeDecision(e.to_string()))
+    }
+
+    pub fn action_mask(&self) -> Vec<bool>{
+        let mut mask = vec![false; self.action_count()];
+        self.game.get_possible_moves()
+            .iter()
+            .filter_map(|e|self.encode_action(e))
+            .for_each(|e|mask[e] = true);
+        mask
+    }
+
+    pub fn observe(&self) -> Observation{
+        let mut tiles = Vec::with_capacity(self.positions.len() * TILE_FEATURES);
+        for pos in self.positions.iter() {
+            let tile = self.game.get_map().get_tile(pos);
+            let visible = tile.is_visible();
+            let known = |index: Option<usize>|match visible {
+                true => index.map_or(0.0, |e|(e + 1) as f32),
+                false => 0.0,
+            };
+            tiles.push(known(index_of(&self.origins, &tile.get_base_field_type().id)));
+            tiles.push(known(tile.get_field_content().and_then(|e|index_of(&self.contents, &e.id))));
+            tiles.push(if visible { 1.0 } else { 0.0 });
+            tiles.push(if self.game.get_quests().contains_key(pos) { 1.0 } else { 0.0 });
+        }
+
+        let mut globals: Vec<f32> = GResource::iter()
+            .map(|e|*self.game.get_resources().get(&e).unwrap_or(&0) as f32)
+            .collect();
+        let state = self.game.get_state();
+        globals.push(state.turn as f32);
+        globals.push(state.max_heroes as f32);
+        globals.push(state.heroes.len() as f32);
+        globals.push(state.history.as_ref().map_or(0.0, |e|e.path_left as f32));
+        for slot in 0..self.config.max_heroes {
+            let hero = state.heroes.get(slot);
+            globals.extend(HeroSkill::iter().map(|s|hero.and_then(|h|h.skills.get(&s)).copied().unwrap_or(0.0)));
+        }
+
+        Observation { tiles, globals, action_mask: self.action_mask() }
+    }
+
+    fn reward(&self, resources_before: &HashMap<GResource, u32>, turn_before: u32, callbacks: &[GameCallback]) -> f32{
+        let reward = &self.config.reward;
+        let resources: f32 = reward.resources.iter()
+            .map(|(resource, weight)|{
+                let before = *resources_before.get(resource).unwrap_or(&0) as f32;
+                let after = *self.game.get_resources().get(resource).unwrap_or(&0) as f32;
+                weight * (after - before)
+            })
+            .sum();
+        let events: f32 = callbacks.iter()
+            .map(|e|match e {
+                GameCallback::NewTileContent { .. } => reward.new_building,
+                GameCallback::NewHero { .. } => reward.new_hero,
+                GameCallback::QuestFinished { success: true, .. } => reward.quest_success,
+                GameCallback::QuestFinished { success: false, .. } => reward.quest_failure,
+                GameCallback::HeroDied { .. } => reward.hero_died,
+                _ => 0.0,
+            })
+            .sum();
+        resources + events + reward.turn * (self.game.get_turn() - turn_before) as f32
+    }
+}
@@ -0,0 +1,179 @@
+//! C API used to embed the game in engines like Godot. The header
+//! `include/stw_game.h` is generated from this file and committed; after
+//! changing the API regenerate it in `stw-game-lib/` with
+//! `cbindgen --config cbindgen.toml --output include/stw_game.h src/ffi.rs`.
+//!
+//! Ownership rules:
+//! * `STWGame*` returned by `stw_game_new` / `stw_game_load` belongs to the
+//!   caller and has to be released with `stw_game_free`.
+//! * Every `char*` returned by a `stw_game_*` function is a new UTF-8, null
+//!   terminated json string owned by the caller and has to be released with
+//!   `stw_string_free`. `NULL` means failure, the reason is in `stw_last_error`.
+//! * The string returned by `stw_last_error` is owned by the library and stays
+//!   valid until the next call on the same thread.
+//! * Strings passed to the library are only borrowed for the duration of the
+//!   call.
+//!
+//! A game is not thread safe, use each `STWGame*` from one thread at a time.
+
+use std::{cell::RefCell, ffi::{c_char, CStr, CString}, panic::{self, AssertUnwindSafe}, ptr};
+
+use serde_json::json;
+
+use crate::{game::{STWGame, GameError, map::TilePos, game_move::GameMove, replay::Replay}, resource::parse_resources, GameConfig};
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+fn set_last_error(msg: &str){
+    let msg = CString::new(msg.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e|*e.borrow_mut() = msg);
+}
+
+/// Runs `f`, storing its error (or panic) in `LAST_ERROR`.
+fn guarded<T>(f: impl FnOnce() -> Result<T, GameError>) -> Option<T>{
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(res)) => Some(res),
+        Ok(Err(e)) => {
+            set_last_error(&e.msg);
+            None
+        },
+        Err(_) => {
+            set_last_error("Game panicked");
+            None
+        },
+    }
+}
+
+unsafe fn read_str<'a>(text: *const c_char, what: &str) -> Result<&'a str, GameError>{
+    if text.is_null() {
+        return Err(GameError::new(format!("{} is NULL", what)));
+    }
+    CStr::from_ptr(text).to_str()
+        .map_err(|_|GameError::new(format!("{} is not valid UTF-8", what)))
+}
+
+unsafe fn read_game<'a>(game: *mut STWGame) -> Result<&'a mut STWGame, GameError>{
+    game.as_mut().ok_or_else(||GameError::new("Game is NULL".to_string()))
+}
+
+fn to_c_string(value: serde_json::Value) -> *mut c_char{
+    CString::new(value.to_string()).map_or(ptr::null_mut(), |e|e.into_raw())
+}
+
+/// Message of the last failed call on this thread, empty if there was none.
+#[no_mangle]
+pub extern "C" fn stw_last_error() -> *const c_char{
+    LAST_ERROR.with(|e|e.borrow().as_ptr())
+}
+
+/// Starts a new game. `resources` is a json array of `{"type", "resource"}`
+/// entries (the content of all files from `res/` merged). `seed` may be
+/// `NULL` for a random seed and `map_size` 0 for the default size.
+///
+/// # Safety
+/// `resources` and `seed` have to be `NULL` or valid C strings.
+#[no_mangle]
+pub unsafe extern "C" fn stw_game_new(resources: *const c_char, seed: *const c_char, map_size: u32) -> *mut STWGame{
+    guarded(||{
+        let mut config = GameConfig::new().set_resources(parse_resources(read_str(resources, "Resources")?)?);
+        if !seed.is_null() {
+            config = config.set_seed(read_str(seed, "Seed")?);
+        }
+        if map_size != 0 {
+            config = config.set_map_size(map_size);
+        }
+        config.start_game()
+    }).map_or(ptr::null_mut(), |e|Box::into_raw(Box::new(e)))
+}
+
+/// Rebuilds a game saved with `stw_game_save`.
+///
+/// # Safety
+/// `path` and `resources` have to be valid C strings.
+#[no_mangle]
+pub unsafe extern "C" fn stw_game_load(path: *const c_char, resources: *const c_char) -> *mut STWGame{
+    guarded(||{
+        let resources = parse_resources(read_str(resources, "Resources")?)?;
+        Replay::load_from_file(read_str(path, "Path")?)?
+            .play(resources)
+            .map_err(|e|GameError::new(e.to_string()))
+    }).map_or(ptr::null_mut(), |e|Box::into_raw(Box::new(e)))
+}
+
+/// Writes the game to `path`. Returns `false` on failure.
+///
+/// # Safety
+/// `game` has to come from this library, `path` has to be a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn stw_game_save(game: *mut STWGame, path: *const c_char) -> bool{
+    guarded(||read_game(game)?.get_replay().save_to_file(read_str(path, "Path")?)).is_some()
+}
+
+/// # Safety
+/// `game` has to be `NULL` or come from this library, and can not be used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn stw_game_free(game: *mut STWGame){
+    if !game.is_null() {
+        drop(Box::from_raw(game));
+    }
+}
+
+/// # Safety
+/// `text` has to be `NULL` or a string returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn stw_string_free(text: *mut c_char){
+    if !text.is_null() {
+        drop(CString::from_raw(text));
+    }
+}
+
+/// Performs a json encoded `GameMove`. Returns `{"callbacks": [...]}` or
+/// `{"bad_move": {"msg": ...}}` when the move is not allowed.
+///
+/// # Safety
+/// `game` has to come from this library, `game_move` has to be a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn stw_game_perform_move(game: *mut STWGame, game_move: *const c_char) -> *mut c_char{
+    guarded(||{
+        let game = read_game(game)?;
+        let game_move: GameMove = serde_json::from_str(read_str(game_move, "Move")?)
+            .map_err(|e|GameError::new(format!("Can not parse move: {}", e)))?;
+        Ok(match game.perform_move(&game_move) {
+            Ok(callbacks) => json!({"callbacks": callbacks}),
+            Err(e) => json!({"bad_move": e}),
+        })
+    }).map_or(ptr::null_mut(), to_c_string)
+}
+
+/// Json array of every move allowed right now.
+///
+/// # Safety
+/// `game` has to come from this library.
+#[no_mangle]
+pub unsafe extern "C" fn stw_game_legal_moves(game: *mut STWGame) -> *mut c_char{
+    guarded(||Ok(json!(read_game(game)?.get_possible_moves()))).map_or(ptr::null_mut(), to_c_string)
+}
+
+/// Json `GameState` of the whole game.
+///
+/// # Safety
+/// `game` has to come from this library.
+#[no_mangle]
+pub unsafe extern "C" fn stw_game_state(game: *mut STWGame) -> *mut c_char{
+    guarded(||Ok(json!(read_game(game)?.get_state()))).map_or(ptr::null_mut(), to_c_string)
+}
+
+/// Json `TileState` of the tile at `(q, r)`, `NULL` outside of the map.
+///
+/// # Safety
+/// `game` has to come from this library.
+#[no_mangle]
+pub unsafe extern "C" fn stw_game_tile(game: *mut STWGame, q: i32, r: i32) -> *mut c_char{
+    guarded(||read_game(game)?.get_tile_state(&TilePos { q, r })
+        .map(|e|json!(e))
+        .ok_or_else(||GameError::new(format!("No tile at ({},{})", q, r)))
+    ).map_or(ptr::null_mut(), to_c_string)
+}
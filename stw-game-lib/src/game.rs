@@ -3,16 +3,28 @@ mod tile;
 mod hero;
 mod quest;
 mod history;
+pub mod replay;
+pub mod state;
+pub mod preview;
+pub mod planner;
+pub mod objectives;
+pub mod summary;
+pub mod market;
+pub mod construction;
+pub mod explain;
 
 pub mod game_controller;
 pub mod game_move;
 
 use std::{collections::HashMap, fmt::Display, rc::Rc};
-use rand::{Rng, distributions::Alphanumeric};
+use rand::{Rng, seq::SliceRandom};
+use rand_pcg::Pcg64;
+use rand_seeder::Seeder;
+use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
-use crate::resource::{ResourceManager, resource_data::{OriginFieldData, FieldTypeData, Globals, HeroData, QuestData, ActionData}, enums::GResource, Resource};
+use crate::resource::{ResourceManager, resources_hash, condition::ConditionFacts, resource_data::{FieldTypeData, QuestData}, enums::{GResource, QuestPenalty, FieldRequirment}, Resource};
 
-use self::{map::*, game_controller::{GameController, GameCallback, hero_controller::HeroController, quest_controller::QuestController}, game_move::{GameMove, PossibleBuilding}, hero::Hero, quest::Quest, history::History};
+use self::{map::*, game_controller::{GameController, GameCallback, hero_controller::HeroController, quest_controller::QuestController, economy_controller::EconomyController, market_controller::MarketController, construction_controller::ConstructionController}, game_move::{GameMove, PossibleBuilding}, hero::Hero, quest::Quest, history::History, replay::Replay, state::{GameState, TileState}, objectives::GameOutcome, construction::Construction};
 
 #[derive(Debug, Clone)]
 pub struct GameError{
@@ -32,7 +44,8 @@ impl GameError {
 }
 
 
-#[derive(Debug, Clone)]
+/// Returned when a `GameMove` is rejected. Serialized as `{"msg": "..."}`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct BadMove{
     pub msg: String
 }
@@ -52,6 +65,24 @@ impl BadMove {
 
 const DEFAULT_MAP_SIZE: u32 = 63;
 const DEFAULT_GAME_NAME: &str = "New Game";
+#[cfg(not(feature = "os-rng"))]
+const DEFAULT_SEED: &str = "STW";
+
+#[cfg(feature = "os-rng")]
+fn random_seed() -> String{
+    rand::thread_rng()
+        .sample_iter(&rand::distributions::Alphanumeric)
+        .take(16)
+        .map(char::from)
+        .collect()
+}
+
+/// Without the `os-rng` feature (e.g. on wasm) there is no entropy source, so
+/// games started without `set_seed` all share this seed.
+#[cfg(not(feature = "os-rng"))]
+fn random_seed() -> String{
+    DEFAULT_SEED.to_string()
+}
 
 pub struct GameConfig{
     resources: Vec<(String, serde_json::Value)>,
@@ -63,13 +94,7 @@ pub struct GameConfig{
 
 impl GameConfig {
     pub fn new() -> GameConfig{
-        let random = rand::thread_rng();
-        let random_seed: String = random
-            .sample_iter(&Alphanumeric)
-            .take(16)
-            .map(char::from)
-            .collect();
-        GameConfig { resources: Vec::new(), name: DEFAULT_GAME_NAME.to_string(), seed: random_seed, map_size: DEFAULT_MAP_SIZE }
+        GameConfig { resources: Vec::new(), name: DEFAULT_GAME_NAME.to_string(), seed: random_seed(), map_size: DEFAULT_MAP_SIZE }
     }
 
     pub fn set_resources(mut self, resources: Vec<(String, serde_json::Value)>) -> Self{
@@ -96,24 +121,16 @@ impl GameConfig {
         if self.resources.len() == 0 {
             return Err(GameError::new(format!("No resources! - add this using builder method set_resources")));
         }
-        let mut rm = ResourceManager::new();
-        self.resources
-            .into_iter()
-            .try_for_each(|e| {
-                match e.0.as_str() {
-                    "Globals" => rm.add_resource::<Globals>(e.1),
-                    "OriginFieldData" => rm.add_resource::<OriginFieldData>(e.1),
-                    "FieldTypeData" => rm.add_resource::<FieldTypeData>(e.1),
-                    "HeroData" => rm.add_resource::<HeroData>(e.1),
-                    "QuestData" => rm.add_resource::<QuestData>(e.1),
-                    "ActionData" => rm.add_resource::<ActionData>(e.1),
-                    other => Err(GameError::new(format!("Unknown type of resource in GameConfig.start_game: {}", other)))
-                }
-            })?;
+        if self.map_size == 0 {
+            return Err(GameError::new("Map size must be at least 1".to_string()));
+        }
+        let replay = Replay::new(&self.name, &self.seed, self.map_size, &resources_hash(&self.resources));
+        let rm = ResourceManager::from_resources(self.resources)?;
 
         let ref_rm: Rc<ResourceManager> = Rc::new(rm);
         let mut res = STWGame::new(&ref_rm)?;
         res.start_game(self.map_size, &self.seed)?;
+        res.replay = replay;
         Ok(res)
     }
 }
@@ -132,6 +149,16 @@ pub struct STWGame{
     _max_additional_learning_hero: u32,
     max_path_length: u32,
     builded: Vec<String>,
+    quests_completed: u32,
+    heroes_died: u32,
+    outcome: Option<GameOutcome>,
+    /// Price raise of every exchange, see `market`.
+    market_demand: HashMap<String, f32>,
+    /// Buildings under construction, in the order they were started.
+    constructions: Vec<Construction>,
+
+    rng: Pcg64,
+    replay: Replay,
 
     resource_manager: Rc<ResourceManager>
 
@@ -140,47 +167,80 @@ pub struct STWGame{
 impl STWGame {
 
     pub fn perform_move(&mut self, game_move: &GameMove) -> Result<Vec<GameCallback>, BadMove>{
-        match game_move {
+        if self.outcome.is_some() {
+            return Err(BadMove::new("Game is over".to_string()));
+        }
+        let mut res = match game_move {
             GameMove::Build(position, id) => self.build(position, id),
             GameMove::Wait => self.wait(),
             GameMove::StartHistory(position, hero_index) => self.start_history(position, *hero_index),
             GameMove::PlayMove(pos, action) => self.play_move(pos, action),
-            GameMove::MakeDecision(_) => todo!(),
-            GameMove::RenameHero(_, _) => todo!(),
+            GameMove::MakeDecision(decision) => self.make_decision(decision),
+            GameMove::RenameHero(hero_index, name) => self.rename_hero(*hero_index, name),
+            GameMove::Trade { give, take, amount } => self.trade(*give, *take, *amount),
+            GameMove::CancelConstruction(pos) => self.cancel_construction(pos),
+            GameMove::Demolish(pos) => self.demolish(pos),
+        };
+        if let Ok(callbacks) = &mut res {
+            let mut outcome = self.check_objectives(callbacks);
+            callbacks.append(&mut outcome);
+            self.replay.record(game_move, callbacks);
         }
+        res
     }
 
     pub fn get_possible_building_at_position(&self, pos: &TilePos) -> Vec<PossibleBuilding>{
         if let Some(tile) = self.map.get(pos){
-            if !tile.is_visible() {
+            if !tile.is_visible() || tile.is_under_construction() {
                 return vec![];
             }
 
-            let adjacent: Vec<Vec<Rc<Resource<FieldTypeData>>>> = self.map.get_tiles_in_range(pos, 5)
-                .iter()
-                .map(|v|v.iter()
-                    .filter_map(|e|e.get_field_content())
-                    .collect()
-                ).collect();
-            let res: Vec<PossibleBuilding> =self.resource_manager
+            let adjacent = self.get_near_contents(pos);
+            let facts = self.get_condition_facts();
+            let mut res: Vec<PossibleBuilding> =self.resource_manager
             .get_resources::<FieldTypeData>()
             .iter()
             .filter(|f|{
-                f.1.is_possible_to_be_build(tile.get_base_field_type().as_ref(), &tile.get_field_content(), &adjacent, tile.get_quest_completed_till_last_build())
+                f.1.is_possible_to_be_build(tile.get_base_field_type().as_ref(), &tile.get_field_content(), &adjacent, tile.get_quest_completed_till_last_build(), &facts)
             })
             .map(|f|
                 PossibleBuilding {
                     id: f.1.id.clone(),
-                    not_enought_resources: !f.1.has_enough_resources(&self.game_resources),
+                    not_enought_resources: !f.1.has_enough_resources(&self.game_resources, &tile.get_field_content()),
                 }
             )
             .collect();
+            res.sort_by(|a, b|a.id.cmp(&b.id));
             return res;
         }
         vec![]
     }
 
+    /// Game state for the game wide leaves of a `Condition`.
+    pub(crate) fn get_condition_facts(&self) -> ConditionFacts<'_>{
+        ConditionFacts {
+            resources: &self.game_resources,
+            turn: self.game_turn,
+            heroes: self.heroes.len(),
+            built: &self.builded
+        }
+    }
+
+    /// Contents of tiles at distance 0 to 5 from `pos`, as used by
+    /// `FieldRequirment::check`.
+    pub(crate) fn get_near_contents(&self, pos: &TilePos) -> Vec<Vec<(TilePos, Rc<Resource<FieldTypeData>>)>>{
+        self.map.get_tiles_in_range(pos, 5)
+            .iter()
+            .map(|v|v.iter()
+                .filter_map(|e|e.get_field_content().map(|c|(e.get_position(), c)))
+                .collect()
+            ).collect()
+    }
+
     pub fn can_wait(&self) -> Result<(), BadMove>{
+        if self.outcome.is_some() {
+            return Err(BadMove::new("Game is over".to_string()));
+        }
         self.is_playing_history()?;
         if self.map.check_if_exists_tile_with_field_path(&self.resource_manager.get_globals().data.win_cond_build_path) {
                 Ok(())
@@ -205,6 +265,60 @@ impl STWGame {
         History::can_start_new(pos, 0, &self)
     }
 
+    pub fn get_possible_decisions(&self) -> Vec<String>{
+        match &self.history {
+            Some(history) => history.get_possible_decisions(self),
+            None => vec![],
+        }
+    }
+
+    /// Every move that `perform_move` would accept right now, except
+    /// `RenameHero`, `Trade`, `CancelConstruction` and `Demolish`. Ordered the
+    /// same way for the same game state.
+    pub fn get_possible_moves(&self) -> Vec<GameMove>{
+        if self.outcome.is_some() {
+            return vec![];
+        }
+        if self.history.is_some() {
+            return self.get_possible_hero_moves()
+                .into_iter()
+                .map(|e|GameMove::PlayMove(e.0, e.1))
+                .chain(self.get_possible_decisions().into_iter().map(GameMove::MakeDecision))
+                .collect();
+        }
+
+        let mut res = vec![];
+        if self.can_wait().is_ok() {
+            res.push(GameMove::Wait);
+        }
+        for (pos, tile) in self.map.iter_sorted() {
+            if tile.is_visible() && !self.quests.contains_key(pos) {
+                res.extend(self.get_possible_building_at_position(pos)
+                    .into_iter()
+                    .filter(|e|!e.not_enought_resources)
+                    .map(|e|GameMove::Build(*pos, e.id)));
+            }
+        }
+        let mut quest_positions: Vec<&TilePos> = self.quests.keys().collect();
+        quest_positions.sort();
+        for pos in quest_positions {
+            res.extend((0..self.heroes.len())
+                .filter(|e|History::can_start_new(pos, *e, self).is_ok())
+                .map(|e|GameMove::StartHistory(*pos, e)));
+        }
+        res
+    }
+
+    /// Serializable snapshot of everything a client needs to draw the game.
+    pub fn get_state(&self) -> GameState{
+        GameState::new(self)
+    }
+
+    /// Snapshot of a single tile, `None` outside of the map.
+    pub fn get_tile_state(&self, pos: &TilePos) -> Option<TileState>{
+        self.map.get(pos).map(|e|TileState::new(e))
+    }
+
     pub fn print(&self){
         self.map.print();
         println!("Resources: {:?}", self.game_resources);
@@ -214,6 +328,26 @@ impl STWGame {
         print!("History {:?}", self.history);
     }
 
+    pub fn print_summary(&self){
+        print!("{}", self.get_summary());
+    }
+
+    pub fn get_history(&self) -> Option<&History>{
+        self.history.as_ref()
+    }
+
+    pub fn get_map(&self) -> &GameMap{
+        &self.map
+    }
+
+    pub fn get_turn(&self) -> u32{
+        self.game_turn
+    }
+
+    pub fn get_resources(&self) -> &HashMap<GResource, u32>{
+        &self.game_resources
+    }
+
     pub fn get_heroes(&self) -> &Vec<Box<Hero>>{
         &self.heroes
     }
@@ -222,8 +356,17 @@ impl STWGame {
         &self.quests
     }
 
+    /// Every move performed so far together with the callbacks it produced.
+    pub fn get_replay(&self) -> &Replay{
+        &self.replay
+    }
+
     //
 
+    pub(crate) fn get_resource_manager(&self) -> &Rc<ResourceManager>{
+        &self.resource_manager
+    }
+
     pub(crate) fn _get_heroes_mut(&mut self) -> &Vec<Box<Hero>>{
         &mut self.heroes
     }
@@ -244,6 +387,13 @@ impl STWGame {
             _max_additional_learning_hero: 0,
             max_path_length: rm.get_globals().data.init_path_length_per_hero,
             builded: Vec::new(),
+            quests_completed: 0,
+            heroes_died: 0,
+            outcome: None,
+            market_demand: HashMap::new(),
+            constructions: Vec::new(),
+            rng: Seeder::from("").make_rng(),
+            replay: Replay::new(DEFAULT_GAME_NAME, "", 0, ""),
             resource_manager: rm.clone(),
             history: None,
         })
@@ -251,6 +401,7 @@ impl STWGame {
 
     pub(crate) fn start_game(&mut self, size: u32, seed: &str) -> Result<(), GameError>{
         self.map.generate(size, seed)?;
+        self.rng = Seeder::from((seed, "game")).make_rng();
         self.game_resources = self.resource_manager.get_globals().data.start_game_resources.clone();
         GResource::iter()
             .for_each(|e|{
@@ -271,23 +422,46 @@ impl STWGame {
     fn build(&mut self, pos: &TilePos, id: &str) ->  Result<Vec<GameCallback>, BadMove>{
         self.can_be_build(pos, id)?;
 
-        self.map.get_mut(pos).expect("Checked in canBeBuild").set_field_content(id).unwrap();
-
         let before = self.game_resources.clone();
 
         let ftd = self.resource_manager.get_resource::<FieldTypeData>(id).unwrap();
-        ftd.spent_resources(&mut self.game_resources);
+        let old = self.map.get(pos).expect("Checked in canBeBuild").get_field_content();
+        let cost = ftd.get_cost(&old);
+        ftd.spent_resources(&mut self.game_resources, &old);
 
-        let mut res = self.play_instant_effects(&ftd);
+        if ftd.data.build_time > 0 {
+            return Ok(self.start_construction(pos, &ftd, cost, &before));
+        }
+        Ok(self.finish_building(pos, &ftd, cost, &before))
+    }
 
-        res.append(&mut self.get_changed_resource_callbacks(&before));
-        res.push(GameCallback::NewTileContent{position: *pos, field_type_id: id.to_string()});
-        self.builded.push(id.to_string());
-        Ok(res)
+    /// Puts the building, for which `cost` was paid, on the tile and plays
+    /// its instant effects. `before` are the resources to report the
+    /// changes against.
+    fn finish_building(&mut self, pos: &TilePos, ftd: &Rc<Resource<FieldTypeData>>, cost: HashMap<GResource, u32>, before: &HashMap<GResource, u32>) -> Vec<GameCallback>{
+        let tile = self.map.get_mut(pos).expect("Checked in canBeBuild");
+        let old = tile.get_field_content();
+        let old_first_built = tile.is_first_built();
+        tile.set_field_content(&ftd.id).unwrap();
+        tile.record_build(cost, old.clone().filter(|e|ftd.is_upgrade_of(e)));
+        tile.set_first_built(!self.builded.contains(&ftd.id));
+
+        let mut res = match old {
+            Some(old) if ftd.data.replacement.undo_effects && ftd.replaces(&old) => self.undo_instant_effects(&old, old_first_built),
+            _ => vec![],
+        };
+        res.append(&mut self.play_instant_effects(pos, ftd));
+
+        res.append(&mut self.get_changed_resource_callbacks(before));
+        res.push(GameCallback::NewTileContent{position: *pos, field_type_id: ftd.id.to_string()});
+        self.builded.push(ftd.id.to_string());
+        res
     }
 
     fn get_changed_resource_callbacks(&self, before: &HashMap<GResource, u32>)-> Vec<GameCallback>{
-        before.iter()
+        let mut before: Vec<_> = before.iter().collect();
+        before.sort_by_key(|e|*e.0);
+        before.into_iter()
         .filter(|e|e.1 != self.game_resources.get(e.0).unwrap())
         .map(|e|GameCallback::ChangedResource{resource: *e.0, new_value: *self.game_resources.get(e.0).unwrap()})
         .collect()
@@ -298,6 +472,12 @@ impl STWGame {
         if self.quests.get(pos).is_some() {
             return Err(BadMove::new(format!("To build here complete quest - position {:?}", pos)));
         }
+        if self.map.get(pos).is_some_and(|e|e.is_under_construction()) {
+            return Err(BadMove::new(format!("Position {:?} is under construction", pos)));
+        }
+        if let Ok(ftd) = self.resource_manager.get_resource::<FieldTypeData>(id) {
+            ftd.check_effects().map_err(|e|BadMove::new(format!("Cant build {} - {}", id, e)))?;
+        }
 
         match self.get_possible_building_at_position(pos)
             .iter()
@@ -311,11 +491,15 @@ impl STWGame {
             }
     }
 
-    fn play_instant_effects(&mut self, ftd: &Rc<Resource<FieldTypeData>>) -> Vec<GameCallback> {
+    fn play_instant_effects(&mut self, pos: &TilePos, ftd: &Rc<Resource<FieldTypeData>>) -> Vec<GameCallback> {
         ftd.data.instant_effects.iter()
             .map(|e|match e {
-                crate::resource::enums::FieldInstantEffect::ShowFields { .. } => {
-                    todo!()
+                crate::resource::enums::FieldInstantEffect::ShowFields { only_first_time, amount } => {
+                    if self.check_if_should_play_effect(ftd, *only_first_time) {
+                        self.show_fields(pos, *amount)
+                    }else{
+                        vec![]
+                    }
                 },
                 crate::resource::enums::FieldInstantEffect::GiveResources { only_first_time, resource, amount } => {
                     if self.check_if_should_play_effect(ftd, *only_first_time) {
@@ -325,10 +509,21 @@ impl STWGame {
                         vec![]
                     }
                 },
-                crate::resource::enums::FieldInstantEffect::IncreaseSkill { .. } => {
-                    todo!()
+                crate::resource::enums::FieldInstantEffect::IncreaseSkill { only_first_time, skill, amount } => {
+                    if self.check_if_should_play_effect(ftd, *only_first_time) {
+                        self.heroes.iter_mut()
+                            .enumerate()
+                            .map(|(hero_number, hero)|{
+                                let value = hero.get_skills_mut().entry(*skill).or_insert(0.0);
+                                *value += amount;
+                                GameCallback::HeroLeveled{hero_number, skill: *skill, new_skill_value: *value}
+                            })
+                            .collect()
+                    }else{
+                        vec![]
+                    }
                 },
-                crate::resource::enums::FieldInstantEffect::LearnAboutAction { .. } => todo!(),
+                crate::resource::enums::FieldInstantEffect::LearnAboutAction { .. } => unreachable!("Rejected in canBeBuild"),
                 crate::resource::enums::FieldInstantEffect::IncreaseMaxHeroes { only_first_time } => {
                     if self.check_if_should_play_effect(ftd, *only_first_time) {
                         self.max_heroes += 1;
@@ -342,16 +537,66 @@ impl STWGame {
             .collect()
     }
 
+    /// Shows `amount` tiles closest to `pos`, counting the ones already visible.
+    fn show_fields(&mut self, pos: &TilePos, amount: u32) -> Vec<GameCallback> {
+        let mut closest = vec![];
+        for distance in 1.. {
+            let ring: Vec<TilePos> = pos.positions_in_distance(distance).into_iter()
+                .filter(|e|self.map.get(e).is_some())
+                .collect();
+            if ring.is_empty() || closest.len() >= amount as usize {
+                break;
+            }
+            closest.extend(ring);
+        }
+        closest.truncate(amount as usize);
+
+        closest.into_iter()
+            .filter_map(|e|{
+                let tile = self.map.get_mut(&e)?;
+                if tile.is_visible() {
+                    return None;
+                }
+                tile.set_visible(true);
+                Some(GameCallback::TileDiscovered{position: e})
+            })
+            .collect()
+    }
+
+    /// Reverts `GiveResources` and `IncreaseMaxHeroes` of replaced or
+    /// demolished content that were played when it was built - the ones
+    /// played only the first time when it was `first_built`.
+    fn undo_instant_effects(&mut self, ftd: &Rc<Resource<FieldTypeData>>, first_built: bool) -> Vec<GameCallback> {
+        ftd.data.instant_effects.iter()
+            .filter_map(|e|match e {
+                crate::resource::enums::FieldInstantEffect::GiveResources { only_first_time, resource, amount } if !only_first_time || first_built => {
+                    self.game_resources.entry(*resource).and_modify(|f| *f = f.saturating_sub(*amount));
+                    None
+                },
+                crate::resource::enums::FieldInstantEffect::IncreaseMaxHeroes { only_first_time } if !only_first_time || first_built => {
+                    self.max_heroes = self.max_heroes.saturating_sub(1);
+                    Some(GameCallback::MaxHeroesDecreased{current_max_heroes: self.max_heroes})
+                },
+                _ => None
+            })
+            .collect()
+    }
+
+    /// Effects are played before the building is added to `builded`, so a
+    /// first time effect plays while it is not there yet.
     fn check_if_should_play_effect(&self, ftd: &Rc<Resource<FieldTypeData>>, only_first_time: bool) -> bool{
-        !only_first_time || self.builded.contains(&ftd.id)
+        !only_first_time || !self.builded.contains(&ftd.id)
     }
 
     fn wait(&mut self) -> Result<Vec<GameCallback>, BadMove>{
         self.can_wait()?;
         self.game_turn += 1;
-        let mut res: Vec<GameCallback> = HeroController::process_game_step( self).unwrap();
+        let mut res: Vec<GameCallback> = ConstructionController::process_game_step(self).unwrap();
+        res.append(&mut EconomyController::process_game_step(self).unwrap());
+        res.append(&mut HeroController::process_game_step( self).unwrap());
         let mut to_append = QuestController::process_game_step( self).unwrap();
         res.append(&mut to_append);
+        res.append(&mut MarketController::process_game_step(self).unwrap());
         Ok(res)
     }
 
@@ -361,6 +606,156 @@ impl STWGame {
         Ok(vec![GameCallback::StartedHistory{quest_pos: *pos, choosen_hero: hero_index}])
     }
 
+    fn make_decision(&mut self, decision_id: &str) -> Result<Vec<GameCallback>, BadMove> {
+        let history = self.history.as_ref()
+            .ok_or_else(||BadMove::new("Game is not playing history and you try to make decision".to_string()))?;
+        if !history.get_possible_decisions(self).iter().any(|e|e == decision_id) {
+            let reason = self.quests.get(&history.quest_pos)
+                .and_then(|q|q.get_quest_type().data.quest_decisions.iter()
+                    .find(|e|e.id == decision_id)
+                    .map(|e|history.check_decision(e, &self.heroes[history.hero_index], &self.get_condition_facts()).err().unwrap_or_default()))
+                .unwrap_or_else(||"no such decision".to_string());
+            return Err(BadMove::new(format!("Decision {} is not possible for quest at pos {:?}: {}", decision_id, history.quest_pos, reason)));
+        }
+
+        let history = self.history.take().expect("Checked above");
+        let quest = self.quests.remove(&history.quest_pos).expect("Decisions are possible only for existing quest");
+        let quest_data = quest.get_quest_type();
+        let decision = quest_data.data.quest_decisions.iter()
+            .find(|e|e.id == decision_id)
+            .expect("Checked in get_possible_decisions");
+
+        let points = history.get_decision_points(decision, &self.heroes[history.hero_index]);
+        let success = self.rng.gen::<f32>() < decision.success_probability(points);
+        let before = self.game_resources.clone();
+
+        let mut res = vec![GameCallback::QuestFinished{quest_pos: history.quest_pos, quest_id: quest_data.id.to_string(), decision: decision_id.to_string(), success}];
+        if success {
+            let mut treasure: Vec<_> = decision.treasure.iter().collect();
+            treasure.sort_by_key(|e|*e.0);
+            for (resource, (min, max)) in treasure {
+                let amount = (min + self.rng.gen::<f32>() * (max - min)) as u32;
+                *self.game_resources.entry(*resource).or_insert(0) += amount;
+            }
+            if let Some(tile) = self.map.get_mut(&history.quest_pos) {
+                tile.set_quest_completed();
+            }
+            self.quests_completed += 1;
+        } else {
+            res.append(&mut self.play_quest_penalties(&quest_data, &history));
+        }
+        res.append(&mut self.get_changed_resource_callbacks(&before));
+        Ok(res)
+    }
+
+    fn play_quest_penalties(&mut self, quest: &Rc<Resource<QuestData>>, history: &History) -> Vec<GameCallback> {
+        let mid = self.map.get_mid_position();
+        let mut res = vec![];
+        for penalty in quest.data.penalty.iter() {
+            match penalty {
+                QuestPenalty::LooseResources { resource, amount } => {
+                    self.game_resources.entry(*resource).and_modify(|v|*v = v.saturating_sub(*amount));
+                },
+                QuestPenalty::DestroyRandomNotMain => {
+                    let candidates = self.get_positions_with_content(|_|true);
+                    if let Some(pos) = candidates.choose(&mut self.rng) {
+                        res.append(&mut self.remove_tile_content(&pos.clone()));
+                    }
+                },
+                QuestPenalty::DestroyTileWithPath(path) => {
+                    let candidates = self.get_positions_with_content(|e|e.data.build_path == *path);
+                    if let Some(pos) = candidates.choose(&mut self.rng) {
+                        res.append(&mut self.remove_tile_content(&pos.clone()));
+                    }
+                },
+                QuestPenalty::DestroyQuestTile => {
+                    if history.quest_pos != mid {
+                        res.append(&mut self.remove_tile_content(&history.quest_pos));
+                    }
+                },
+                QuestPenalty::KillHero(probability) => {
+                    if self.rng.gen::<f32>() < *probability && history.hero_index < self.heroes.len() {
+                        self.heroes.remove(history.hero_index);
+                        self.heroes_died += 1;
+                        res.push(GameCallback::HeroDied{hero_number: history.hero_index});
+                    }
+                },
+            }
+        }
+        res
+    }
+
+    /// Sorted positions of tiles (other than the map middle) whose content passes `filter`.
+    fn get_positions_with_content<F: Fn(&Resource<FieldTypeData>) -> bool>(&self, filter: F) -> Vec<TilePos> {
+        let mid = self.map.get_mid_position();
+        self.map.iter_sorted()
+            .into_iter()
+            .filter(|e|*e.0 != mid && e.1.get_field_content().is_some_and(|c|filter(&c)))
+            .map(|e|*e.0)
+            .collect()
+    }
+
+    fn remove_tile_content(&mut self, pos: &TilePos) -> Vec<GameCallback> {
+        match self.map.get_mut(pos) {
+            Some(tile) if tile.get_field_content().is_some() => {
+                tile.remove_field_content();
+                vec![GameCallback::TileContentRemoved{position: *pos}]
+            },
+            _ => vec![],
+        }
+    }
+
+    /// Undoes the instant effects of the demolished building, puts back the
+    /// building it was upgraded from and refunds `Globals::demolish_refund`
+    /// of the cost paid for it, rounded down.
+    fn demolish(&mut self, pos: &TilePos) -> Result<Vec<GameCallback>, BadMove> {
+        self.is_playing_history()?;
+        let tile = self.map.get(pos)
+            .ok_or_else(||BadMove::new(format!("No tile at position {:?}", pos)))?;
+        let content = tile.get_field_content()
+            .ok_or_else(||BadMove::new(format!("Nothing to demolish at position {:?}", pos)))?;
+        if *pos == self.map.get_mid_position() {
+            return Err(BadMove::new("Can not demolish the middle of the map".to_string()));
+        }
+        if self.quests.contains_key(pos) {
+            return Err(BadMove::new(format!("To demolish here complete quest - position {:?}", pos)));
+        }
+        if tile.is_under_construction() {
+            return Err(BadMove::new(format!("Position {:?} is under construction", pos)));
+        }
+        if content.has_requirment(|e|matches!(e, FieldRequirment::CantDemolish)) {
+            return Err(BadMove::new(format!("Building {} can not be demolished", content.id)));
+        }
+
+        let tile = self.map.get_mut(pos).expect("Checked above");
+        let first_built = tile.is_first_built();
+        let paid = tile.downgrade();
+        let previous = tile.get_field_content();
+
+        let before = self.game_resources.clone();
+        let mut res = self.undo_instant_effects(&content, first_built);
+        let share = self.resource_manager.get_globals().data.demolish_refund.clamp(0.0, 1.0);
+        for (resource, amount) in paid.iter() {
+            *self.game_resources.entry(*resource).or_insert(0) += (*amount as f32 * share).floor() as u32;
+        }
+        res.append(&mut self.get_changed_resource_callbacks(&before));
+        res.push(GameCallback::TileContentRemoved{position: *pos});
+        if let Some(previous) = previous {
+            res.push(GameCallback::BuildingDowngraded{position: *pos, field_type_id: previous.id.to_string()});
+        }
+        Ok(res)
+    }
+
+    fn rename_hero(&mut self, hero_index: usize, name: &str) -> Result<Vec<GameCallback>, BadMove> {
+        if name.trim().is_empty() {
+            return Err(BadMove::new("Hero name can not be empty".to_string()));
+        }
+        let hero = self.heroes.get_mut(hero_index)
+            .ok_or_else(||BadMove::new(format!("No hero with index {:?}", hero_index)))?;
+        hero.set_name(name);
+        Ok(vec![GameCallback::HeroRenamed{hero_number: hero_index, name: name.to_string()}])
+    }
+
     fn is_waiting_for_decision(&self) ->Result<(), BadMove>{
         match &self.history {
             Some(history) => if history.path_left == 0 {
@@ -385,9 +780,13 @@ impl STWGame {
             Some(_) => {
                 if self.history.as_ref().unwrap().get_possible_next_move(&self)
                     .contains(&(*pos, action.to_string())) {
-                        Ok(self.history.as_mut().unwrap().perform_move(*pos, action.to_string(), &self.resource_manager, &mut self.heroes))
+                        Ok(self.history.as_mut().unwrap().perform_move(*pos, action.to_string(), &self.resource_manager, &mut self.heroes, &mut self.rng))
                 } else {
-                    Err(BadMove::new(format!("Move hero to pos {:?} and do action {} is inpossible", &pos, action)))
+                    let reason = match self.history.as_ref().unwrap().check_action(action, self) {
+                        Err(e) if !action.is_empty() => format!(": {}", e),
+                        _ => String::new(),
+                    };
+                    Err(BadMove::new(format!("Move hero to pos {:?} and do action {} is inpossible{}", &pos, action, reason)))
                 }
             },
             None =>  Err(BadMove::new(format!("Game is not playing history and you try move hero"))),
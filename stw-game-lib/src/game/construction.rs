@@ -0,0 +1,54 @@
+//! Buildings with a `build_time`. They are paid for when started, take the
+//! tile until finished and only then replace its content and play their
+//! instant effects.
+
+use std::{collections::HashMap, rc::Rc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::resource::{Resource, enums::GResource, resource_data::FieldTypeData};
+
+use super::{STWGame, BadMove, map::TilePos, game_controller::GameCallback};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Construction{
+    pub position: TilePos,
+    pub id: String,
+    pub turns_left: u32,
+    /// Paid when started.
+    pub cost: HashMap<GResource, u32>
+}
+
+impl STWGame {
+    /// Buildings under construction, in the order they were started.
+    pub fn get_constructions(&self) -> &[Construction]{
+        &self.constructions
+    }
+
+    pub(super) fn start_construction(&mut self, pos: &TilePos, ftd: &Rc<Resource<FieldTypeData>>, cost: HashMap<GResource, u32>, before: &HashMap<GResource, u32>) -> Vec<GameCallback>{
+        self.map.get_mut(pos).expect("Checked in canBeBuild").set_construction(&ftd.id).unwrap();
+        self.constructions.push(Construction { position: *pos, id: ftd.id.to_string(), turns_left: ftd.data.build_time, cost });
+
+        let mut res = self.get_changed_resource_callbacks(before);
+        res.push(GameCallback::ConstructionProgress { position: *pos, field_type_id: ftd.id.to_string(), turns_left: ftd.data.build_time });
+        res
+    }
+
+    /// Refunds `Globals::construction_refund` of the paid cost, rounded down.
+    pub(super) fn cancel_construction(&mut self, pos: &TilePos) -> Result<Vec<GameCallback>, BadMove>{
+        self.is_playing_history()?;
+        let index = self.constructions.iter().position(|e|e.position == *pos)
+            .ok_or_else(||BadMove::new(format!("Nothing is under construction at position {:?}", pos)))?;
+        let construction = self.constructions.remove(index);
+        self.map.get_mut(pos).expect("Construction is on the map").clear_construction();
+
+        let before = self.game_resources.clone();
+        let share = self.resource_manager.get_globals().data.construction_refund.clamp(0.0, 1.0);
+        for (resource, amount) in construction.cost.iter() {
+            *self.game_resources.entry(*resource).or_insert(0) += (*amount as f32 * share).floor() as u32;
+        }
+        let mut res = self.get_changed_resource_callbacks(&before);
+        res.push(GameCallback::ConstructionCancelled { position: *pos, field_type_id: construction.id });
+        Ok(res)
+    }
+}
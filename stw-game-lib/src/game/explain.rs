@@ -0,0 +1,98 @@
+//! Why a building can or can not be built at a tile, one verdict per check,
+//! so clients can show it instead of the building just missing from
+//! `get_possible_building_at_position`.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::resource::{enums::GResource, resource_data::FieldTypeData};
+
+use super::{STWGame, BadMove, map::TilePos};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Verdict{
+    /// What was checked, e.g. `NoNearFieldWithPath town within 5`.
+    pub requirment: String,
+    pub met: bool,
+    /// Why it is not met, e.g. `violated by village at (9,18)`.
+    pub reason: Option<String>
+}
+
+impl Verdict {
+    fn new(requirment: String, result: Result<(), String>) -> Verdict{
+        let reason = result.err().map(|e|{
+            let prefix = format!("{}: ", requirment);
+            e.strip_prefix(&prefix).map(|e|e.to_string()).unwrap_or(e)
+        });
+        Verdict { requirment, met: reason.is_none(), reason }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BuildingExplanation{
+    pub position: TilePos,
+    pub id: String,
+    /// Same answer as the `Build` move would give.
+    pub buildable: bool,
+    /// Checks of the tile itself - history, visibility, quest and construction.
+    pub tile: Vec<Verdict>,
+    /// Path level or replacement of the current content.
+    pub placement: Verdict,
+    /// One per top level condition of the building.
+    pub requirments: Vec<Verdict>,
+    /// Instant effects the game can not play yet, like `LearnAboutAction`.
+    pub effects: Verdict,
+    /// Missing amount of every resource the player can not pay.
+    pub shortfalls: BTreeMap<GResource, u32>
+}
+
+impl STWGame {
+    pub fn explain_building(&self, pos: &TilePos, id: &str) -> Result<BuildingExplanation, BadMove>{
+        let tile = self.map.get(pos)
+            .ok_or_else(||BadMove::new(format!("There is no tile at position {:?}", pos)))?;
+        let ftd = self.resource_manager.get_resource::<FieldTypeData>(id)
+            .map_err(|_|BadMove::new(format!("There is no building {}", id)))?;
+
+        let check = |met: bool, reason: &str|match met {
+            true => Ok(()),
+            false => Err(reason.to_string()),
+        };
+        let tile_verdicts = vec![
+            Verdict::new("NoHistory".to_string(), self.is_playing_history().map_err(|e|e.msg)),
+            Verdict::new("Visible".to_string(), check(tile.is_visible(), "tile is not discovered yet")),
+            Verdict::new("NoQuest".to_string(), check(!self.quests.contains_key(pos), "complete the quest here first")),
+            Verdict::new("NotUnderConstruction".to_string(), check(!tile.is_under_construction(), "tile is under construction")),
+        ];
+
+        let old = tile.get_field_content();
+        let placement = Verdict::new(
+            match &old {
+                Some(old) => format!("BuildOn {}", old.id),
+                None => "BuildOnEmpty".to_string(),
+            },
+            ftd.check_placement(&old)
+        );
+
+        let origin = tile.get_base_field_type();
+        let near = self.get_near_contents(pos);
+        let facts = self.get_condition_facts();
+        let requirments: Vec<Verdict> = ftd.data.requirments.iter()
+            .map(|e|Verdict::new(e.describe(), e.check(&facts, &|r|r.check(&origin, &near, tile.get_quest_completed_till_last_build()))))
+            .collect();
+
+        let effects = Verdict::new("SupportedEffects".to_string(), ftd.check_effects());
+
+        let shortfalls: BTreeMap<GResource, u32> = ftd.get_cost(&old).iter()
+            .map(|(res, amount)|(*res, amount.saturating_sub(*self.game_resources.get(res).unwrap_or(&0))))
+            .filter(|e|e.1 > 0)
+            .collect();
+
+        let buildable = tile_verdicts.iter().all(|e|e.met)
+            && placement.met
+            && requirments.iter().all(|e|e.met)
+            && effects.met
+            && shortfalls.is_empty();
+        Ok(BuildingExplanation { position: *pos, id: id.to_string(), buildable, tile: tile_verdicts, placement, requirments, effects, shortfalls })
+    }
+}
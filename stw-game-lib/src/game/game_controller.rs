@@ -1,23 +1,51 @@
-use crate::resource::enums::{GResource, HeroSkill};
+use serde::{Deserialize, Serialize};
+
+use crate::resource::enums::{GResource, HeroSkill, LossCondition, WinCondition};
 
 use super::{STWGame, map::TilePos, GameError};
 
 pub mod hero_controller;
 pub mod quest_controller;
+pub mod economy_controller;
+pub mod market_controller;
+pub mod construction_controller;
 
 pub(crate) trait GameController {
     fn process_game_step(game: &mut STWGame) -> Result<Vec<GameCallback>, GameError>;
 }
 
 
-#[derive(Debug, Clone, PartialEq)]
+/// Event produced by `STWGame::perform_move`. Part of the JSON protocol
+/// (see `game_move`), e.g.
+/// `{"type": "ChangedResource", "data": {"resource": "Gold", "new_value": 3}}`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", content = "data")]
 pub enum GameCallback {
     ChangedResource{resource: GResource, new_value: u32},
     NewTileContent{position: TilePos, field_type_id: String},
     MaxHeroesIncreased{current_max_heroes: u32},
+    /// Replaced building's `IncreaseMaxHeroes` was undone.
+    MaxHeroesDecreased{current_max_heroes: u32},
     NewHero{where_born: TilePos, hero_id: String},
     NewQuest{where_created: TilePos, quest_id: String},
     StartedHistory{quest_pos: TilePos , choosen_hero: usize},
     HeroLeveled{hero_number: usize, skill: HeroSkill, new_skill_value: f32},
-    HeroMoved{dest_position: TilePos, hero_number: usize, success: f32, action_performed: String}
+    HeroMoved{dest_position: TilePos, hero_number: usize, success: f32, action_performed: String},
+    HeroRenamed{hero_number: usize, name: String},
+    HeroDied{hero_number: usize},
+    QuestFinished{quest_pos: TilePos, quest_id: String, decision: String, success: bool},
+    TileContentRemoved{position: TilePos},
+    /// Hidden tile shown by a `ShowFields` effect.
+    TileDiscovered{position: TilePos},
+    BuildingActiveChanged{position: TilePos, active: bool},
+    /// `turns_left` of a construction, also sent when it is started.
+    ConstructionProgress{position: TilePos, field_type_id: String, turns_left: u32},
+    /// Followed by `NewTileContent`.
+    ConstructionFinished{position: TilePos, field_type_id: String},
+    ConstructionCancelled{position: TilePos, field_type_id: String},
+    /// After `TileContentRemoved` of a demolished building, with the
+    /// building it was upgraded from now on the tile.
+    BuildingDowngraded{position: TilePos, field_type_id: String},
+    GameWon{condition: WinCondition, score: u32},
+    GameLost{condition: LossCondition, score: u32}
 }
\ No newline at end of file
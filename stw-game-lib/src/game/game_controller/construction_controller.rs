@@ -0,0 +1,28 @@
+use super::GameController;
+use crate::{game::{GameCallback, GameError, STWGame}, resource::resource_data::FieldTypeData};
+
+/// Advances every construction by one turn and finishes the ones that are
+/// done. Goes before the economy, so a finished building already pays upkeep
+/// and produces in the same turn.
+pub(crate) struct ConstructionController{
+
+}
+
+impl GameController for ConstructionController {
+    fn process_game_step(game: &mut STWGame) -> Result<Vec<GameCallback>, GameError> {
+        let mut res = vec![];
+        for mut construction in std::mem::take(&mut game.constructions) {
+            construction.turns_left -= 1;
+            if construction.turns_left > 0 {
+                res.push(GameCallback::ConstructionProgress { position: construction.position, field_type_id: construction.id.to_string(), turns_left: construction.turns_left });
+                game.constructions.push(construction);
+                continue;
+            }
+            let ftd = game.resource_manager.get_resource::<FieldTypeData>(&construction.id)?;
+            let before = game.game_resources.clone();
+            res.push(GameCallback::ConstructionFinished { position: construction.position, field_type_id: construction.id.to_string() });
+            res.append(&mut game.finish_building(&construction.position, &ftd, construction.cost, &before));
+        }
+        Ok(res)
+    }
+}
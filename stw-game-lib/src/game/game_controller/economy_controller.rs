@@ -0,0 +1,59 @@
+use std::collections::BTreeMap;
+
+use super::GameController;
+use crate::{game::{GameCallback, GameError, STWGame}, resource::enums::GResource};
+
+/// Pays upkeep of every building and collects production of the active ones.
+/// Upkeep goes first and in position order, so the buildings that go inactive
+/// do not depend on this turn's production.
+pub(crate) struct EconomyController{
+
+}
+
+impl GameController for EconomyController {
+    fn process_game_step(game: &mut STWGame) -> Result<Vec<GameCallback>, GameError> {
+        let before = game.game_resources.clone();
+        let mut res = vec![];
+        let buildings: Vec<_> = game.map.iter_sorted()
+            .into_iter()
+            .filter_map(|e|e.1.get_field_content().map(|c|(*e.0, c)))
+            .collect();
+
+        for (pos, content) in buildings.iter() {
+            let paid = content.data.upkeep.iter()
+                .all(|e|game.game_resources.get(e.0).unwrap_or(&0) >= e.1);
+            if paid {
+                for (resource, amount) in content.data.upkeep.iter() {
+                    *game.game_resources.entry(*resource).or_insert(0) -= amount;
+                }
+            }
+            let tile = game.map.get_mut(pos).expect("Position taken from the map");
+            if tile.is_active() != paid {
+                tile.set_active(paid);
+                res.push(GameCallback::BuildingActiveChanged{position: *pos, active: paid});
+            }
+        }
+
+        let mut produced: BTreeMap<GResource, u32> = BTreeMap::new();
+        for (pos, content) in buildings.iter() {
+            let tile = game.map.get(pos).expect("Position taken from the map");
+            if !tile.is_active() {
+                continue;
+            }
+            let production = if content.data.conditional_production.is_empty() {
+                content.data.production.clone()
+            } else {
+                content.get_production(&tile.get_base_field_type(), &game.get_near_contents(pos), tile.get_quest_completed_till_last_build(), &game.get_condition_facts())
+            };
+            for (resource, amount) in production {
+                *produced.entry(resource).or_insert(0) += amount;
+            }
+        }
+        for (resource, amount) in produced {
+            *game.game_resources.entry(resource).or_insert(0) += amount;
+        }
+
+        res.append(&mut game.get_changed_resource_callbacks(&before));
+        Ok(res)
+    }
+}
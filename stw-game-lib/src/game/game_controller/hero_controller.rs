@@ -1,6 +1,6 @@
 use std::rc::Rc;
 
-use rand::seq::SliceRandom;
+use rand::{seq::SliceRandom, Rng};
 
 use super::GameController;
 use crate::{game::{GameCallback, map::TilePos, hero::Hero, GameError}, resource::{resource_data::HeroData, Resource}};
@@ -16,14 +16,14 @@ impl GameController for HeroController {
             let mut sum_tile_levels = 0.0;
             let mut prob: Vec<(TilePos, f32)> = vec![];
 
-            for (pos, tile) in game.map.iter(){
+            for (pos, tile) in game.map.iter_sorted(){
                 if let Some(content) = tile.get_field_content() {
                     prob.push((*pos, sum_tile_levels + content.data.hero_levels.len() as f32));
                     sum_tile_levels +=  content.data.hero_levels.len() as f32;
                 }
             }
 
-            let num: f32 = rand::random::<f32>() * sum_tile_levels;
+            let num: f32 = game.rng.gen::<f32>() * sum_tile_levels;
             let mut index = 0;
             while index < prob.len() && prob[index].1 < num {
                 index+=1;
@@ -37,7 +37,7 @@ impl GameController for HeroController {
             
             let sum_tile_levels: f32 = prob.iter().sum();
             
-            let num: f32 = rand::random::<f32>() * sum_tile_levels;
+            let num: f32 = game.rng.gen::<f32>() * sum_tile_levels;
             let mut index = 0;
             let mut act_sum = 0.0;
             while index < prob.len() && prob[index] + act_sum < num {
@@ -47,12 +47,13 @@ impl GameController for HeroController {
 
             let choosen_hero_level = index as u32;
             
-            let hero_id = &game.resource_manager.get_resources::<HeroData>()
-                .iter()
-                .filter(|e|&e.1.data.hero_family == choosen_hero_family && e.1.data.hero_level == choosen_hero_level)
-                .map(|e|e.1)
-                .collect::<Vec<&Rc<Resource<HeroData>>>>()
-                .choose(&mut rand::thread_rng())
+            let mut candidates = game.resource_manager.get_resources::<HeroData>()
+                .into_values()
+                .filter(|e|&e.data.hero_family == choosen_hero_family && e.data.hero_level == choosen_hero_level)
+                .collect::<Vec<Rc<Resource<HeroData>>>>();
+            candidates.sort_by(|a, b|a.id.cmp(&b.id));
+            let hero_id = &candidates
+                .choose(&mut game.rng)
                 .ok_or_else(||GameError::new(format!("Game badly configured, there are lack of hero [level:{}, family:{}] that shoul be in resources", choosen_hero_level, choosen_hero_family)))?
                 .id.to_string();
             
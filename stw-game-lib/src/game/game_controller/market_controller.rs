@@ -0,0 +1,19 @@
+use super::GameController;
+use crate::{game::{GameCallback, GameError, STWGame}, resource::resource_data::ExchangeData};
+
+/// Lets market prices fall back by the `recovery` of every exchange.
+pub(crate) struct MarketController{
+
+}
+
+impl GameController for MarketController {
+    fn process_game_step(game: &mut STWGame) -> Result<Vec<GameCallback>, GameError> {
+        let exchanges = game.resource_manager.get_resources::<ExchangeData>();
+        game.market_demand.retain(|id, demand|{
+            let recovery = exchanges.get(id.as_str()).map_or(1.0, |e|e.data.recovery);
+            *demand *= 1.0 - recovery;
+            *demand > 0.001
+        });
+        Ok(vec![])
+    }
+}
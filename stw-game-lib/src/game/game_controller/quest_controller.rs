@@ -1,6 +1,6 @@
 use std::rc::Rc;
 
-use rand::seq::SliceRandom;
+use rand::{seq::SliceRandom, Rng};
 
 use super::GameController;
 use crate::{game::{GameCallback, GameError, map::TilePos, quest::Quest}, resource::{resource_data::QuestData, Resource}};
@@ -17,7 +17,10 @@ impl GameController for QuestController {
         let mut tiles_with_quests: Vec<TilePos> = game.quests.keys().into_iter()
             .map(|e|*e).collect();
 
-        for (pos, tile) in game.map.iter(){
+        for (pos, tile) in game.map.iter_sorted(){
+            if tile.is_under_construction() {
+                continue;
+            }
             if let Some(content) = tile.get_field_content() {
                 let f_t = tiles_with_quests.iter()
                     .enumerate()
@@ -37,7 +40,7 @@ impl GameController for QuestController {
             return Ok(vec![]);
         }
 
-        let num: f32 = rand::random::<f32>() * sum_tile_levels;
+        let num: f32 = game.rng.gen::<f32>() * sum_tile_levels;
         let mut index = 0;
         while index < prob.len() && prob[index].1 <= num {
             index+=1;
@@ -51,7 +54,7 @@ impl GameController for QuestController {
             
         let sum_tile_levels: f32 = prob.iter().sum();
             
-        let num: f32 = rand::random::<f32>() * sum_tile_levels;
+        let num: f32 = game.rng.gen::<f32>() * sum_tile_levels;
         let mut index = 0;
         let mut act_sum = 0.0;
         while index < prob.len() && prob[index] + act_sum <= num {
@@ -61,12 +64,13 @@ impl GameController for QuestController {
 
         let choosen_quest_level = index as u32;
 
-        let quest_id = &game.resource_manager.get_resources::<QuestData>()
-            .iter()
-            .filter(|e|&e.1.data.quest_family == choosen_quest_family && e.1.data.quest_level == choosen_quest_level)
-            .map(|e|e.1)
-            .collect::<Vec<&Rc<Resource<QuestData>>>>()
-            .choose(&mut rand::thread_rng())
+        let mut candidates = game.resource_manager.get_resources::<QuestData>()
+            .into_values()
+            .filter(|e|&e.data.quest_family == choosen_quest_family && e.data.quest_level == choosen_quest_level)
+            .collect::<Vec<Rc<Resource<QuestData>>>>();
+        candidates.sort_by(|a, b|a.id.cmp(&b.id));
+        let quest_id = &candidates
+            .choose(&mut game.rng)
             .ok_or_else(||GameError::new(format!("Game badly configured, there are lack of quest [level:{}, family:{}] that shoul be in resources", choosen_quest_level, choosen_quest_family)))?
             .id.to_string();
             
@@ -1,16 +1,50 @@
+//! Moves a player (or an external client) can send to `STWGame::perform_move`.
+//!
+//! `GameMove`, `PossibleBuilding`, `GameCallback` and `BadMove` have a stable
+//! JSON representation, described in `docs/json-protocol.md`. Enums are
+//! adjacently tagged: `{"type": "<Variant>", "data": <payload>}`, where the
+//! payload is omitted for unit variants. Variant and field names are part of
+//! the protocol - they are only ever added, never renamed or removed.
+
+use serde::{Deserialize, Serialize};
+
+use crate::resource::enums::GResource;
+
 use super::map::TilePos;
 
+/// ```json
+/// {"type": "Build", "data": [{"q": 8, "r": 18}, "cottage"]}
+/// {"type": "Wait"}
+/// {"type": "Trade", "data": {"give": "Gold", "take": "GreenTrophy", "amount": 1}}
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", content = "data")]
 pub enum GameMove{
     Build(TilePos, String),
     Wait,
     StartHistory(TilePos, usize),
     PlayMove(TilePos, String),
     MakeDecision(String),
-    RenameHero(usize, String)
+    /// Not listed by `STWGame::get_possible_moves` - names are free text.
+    RenameHero(usize, String),
+    /// Buys `amount` of `take` for `give` on the market. Not listed by
+    /// `STWGame::get_possible_moves` (and so not in the env action mask or
+    /// bots) - `amount` is open ended, see `STWGame::get_market`.
+    Trade{give: GResource, take: GResource, amount: u32},
+    /// Stops the construction at the position, returning part of the cost.
+    /// Not listed by `STWGame::get_possible_moves`, it only loses resources.
+    CancelConstruction(TilePos),
+    /// Puts back the building the one at the position was upgraded from, or
+    /// clears the tile, returning part of the cost paid for it. Not listed by
+    /// `STWGame::get_possible_moves`, it only loses progress.
+    Demolish(TilePos)
 }
 
-#[derive(Debug)]
+/// ```json
+/// {"id": "cottage", "not_enought_resources": false}
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct PossibleBuilding{
     pub id: String,
     pub not_enought_resources: bool
-}
\ No newline at end of file
+}
@@ -48,6 +48,18 @@ impl Hero{
         })
     }
 
+    pub fn get_background(&self) -> Rc<Resource<HeroData>>{
+        self.background.clone()
+    }
+
+    pub fn get_name(&self) -> &str{
+        &self.name
+    }
+
+    pub fn set_name(&mut self, name: &str){
+        self.name = name.to_string();
+    }
+
     pub fn get_skills(&self) -> &HashMap<HeroSkill, f32>{
         &self.skills
     }
@@ -1,9 +1,13 @@
 use std::{collections::HashMap, rc::Rc, fmt::Debug};
 
-use crate::resource::{enums::{HeroSkill, FieldCharacteristic, ActionRequirment}, resource_data::ActionData, Resource, ResourceManager};
+use rand::Rng;
+use rand_pcg::Pcg64;
+
+use crate::resource::{enums::{HeroSkill, FieldCharacteristic, ActionRequirment, PointRequirment}, resource_data::{ActionData, QuestDecision}, condition::{ConditionFacts, check_all}, Resource, ResourceManager};
 
 use super::{map::TilePos, STWGame, BadMove, tile::GameTile, game_controller::GameCallback, hero::Hero};
 
+#[derive(Clone)]
 pub struct History{
     pub hero_index: usize,
     pub quest_pos: TilePos,
@@ -105,57 +109,53 @@ impl History{
         res
     }
 
-    fn is_action_permited(&self, id: &String, game: &STWGame) ->bool{
-        let action = game.resource_manager.get_resource::<ActionData>(id)
-            .expect(format!("No action with id {} that should be in resources", id).as_str());
-
-        action.data.requirments.iter()
-            .all(|req|Self::action_meats_requirment(&self.steps, req))
+    fn is_action_permited(&self, id: &str, game: &STWGame) ->bool{
+        self.check_action(id, game).is_ok()
+    }
 
+    /// Why the action can not be performed next, if it can not.
+    pub fn check_action(&self, id: &str, game: &STWGame) -> Result<(), String>{
+        // actions listed by a field but missing from resources can not be performed
+        let action = game.resource_manager.get_resource::<ActionData>(id)
+            .map_err(|_|format!("no action {}", id))?;
+        check_all(&action.data.requirments, &game.get_condition_facts(), &|req|Self::action_meats_requirment(&self.steps, req))
     }
 
-    fn action_meats_requirment(steps: &Vec<(TilePos, Option<Rc<Resource<ActionData>>>)>, req: &ActionRequirment)->bool{
-        match req {
+    fn action_meats_requirment(steps: &[(TilePos, Option<Rc<Resource<ActionData>>>)], req: &ActionRequirment)->Result<(), String>{
+        let met = match req {
             crate::resource::enums::ActionRequirment::IsBefore(before) => 
                 steps.iter().find(|e|e.1.as_ref().map_or("", |v|&v.id) == before).is_some(),
             crate::resource::enums::ActionRequirment::IsBeforeFamily(before) => 
                 steps.iter().find(|e|e.1.as_ref().map_or("", |v|&v.data.action_family) == *before).is_some(),
             crate::resource::enums::ActionRequirment::IsBeforePosition(pos) => steps.len() < *pos,
+        };
+        match met {
+            true => Ok(()),
+            false => Err(format!("{} is not met", req)),
         }
     }
 
 
-    pub fn perform_move(&mut self, pos: TilePos, action: String, rm: &ResourceManager, heroes: &mut Vec<Box<Hero>>) -> Vec<GameCallback> {
+    pub fn perform_move(&mut self, pos: TilePos, action: String, rm: &ResourceManager, heroes: &mut [Box<Hero>], rng: &mut Pcg64) -> Vec<GameCallback> {
         if action.len() != 0 {
             let action = rm.get_resource::<ActionData>(&action)
             .expect(format!("No action with id {} that should be in choosen_heroresources", &action).as_str());
 
-            let rand_succes: f32 = rand::random();
-            
-            action.data.points
-                .iter()
-                .map(|e|(*e.0, self.current_modificators.get(e.0).unwrap_or(&1.0) * (rand_succes * (e.1.1 -e.1.0) + e.1.0)))
-                .for_each(|e|*self.points_got.entry(e.0).or_insert(0.0) += e.1);
-
-            action.data.bonus_points
-                .iter()
-                .filter(|v|Self::action_meats_requirment(&self.steps, &v.0))
-                .flat_map(|v|&v.1)
-                .map(|e|(*e.0, self.current_modificators.get(e.0).unwrap_or(&1.0) * (rand_succes * (e.1.1 -e.1.0) + e.1.0)))
-                .for_each(|e|*self.points_got.entry(e.0).or_insert(0.0) += e.1);
+            let rand_succes: f32 = rng.gen();
 
-            action.data.modificators
-                .iter()
-                .for_each(|e|{
-                    self.current_modificators.entry(*e.0).and_modify(|v|*v+=*e.1);
-            });
+            for (skill, points) in self.roll_points(&action, rand_succes) {
+                *self.points_got.entry(skill).or_insert(0.0) += points;
+            }
+            self.apply_modificators(&action);
 
             let mut res = vec![];
             res.push(GameCallback::HeroMoved{dest_position: pos, hero_number: self.hero_index, success: rand_succes, action_performed: action.id.to_string()});
 
             if 1.0 - action.data.eternal_modificator.probability < rand_succes {
-                action.data.eternal_modificator.skills
-                    .iter()
+                let mut skills: Vec<_> = action.data.eternal_modificator.skills.iter().collect();
+                skills.sort_by_key(|e|*e.0);
+                skills
+                    .into_iter()
                     .for_each(|e|{
                         heroes[self.hero_index]
                             .get_skills_mut()
@@ -164,16 +164,115 @@ impl History{
                     });
             }
 
-            self.steps.push((pos, Some(action)));
-            self.current_pos = Some(pos);
-            self.path_left -= 1;
+            self.advance(pos, Some(action));
             res
         }else{
-            self.steps.push((pos, None));
+            self.advance(pos, None);
             vec![GameCallback::HeroMoved{dest_position: pos, hero_number: self.hero_index, success: 0.0, action_performed: String::from("")}]
         }
 
     }
 
+    /// Points the action gives for a `rand_succes` roll, `points` first and
+    /// then every met `bonus_points` entry. The same roll is used for every skill.
+    pub fn roll_points(&self, action: &Resource<ActionData>, rand_succes: f32) -> Vec<(HeroSkill, f32)> {
+        let points = action.data.points.iter();
+        let bonus_points = action.data.bonus_points
+            .iter()
+            .filter(|v|Self::action_meats_requirment(&self.steps, &v.0).is_ok())
+            .flat_map(|v|&v.1);
+        points.chain(bonus_points)
+            .map(|e|(*e.0, self.current_modificators.get(e.0).unwrap_or(&1.0) * (rand_succes * (e.1.1 -e.1.0) + e.1.0)))
+            .collect()
+    }
+
+    /// Adds the action modificators to skills the hero has.
+    fn apply_modificators(&mut self, action: &Resource<ActionData>) {
+        action.data.modificators
+            .iter()
+            .for_each(|e|{
+                self.current_modificators.entry(*e.0).and_modify(|v|*v+=*e.1);
+        });
+    }
+
+    fn advance(&mut self, pos: TilePos, action: Option<Rc<Resource<ActionData>>>) {
+        self.steps.push((pos, action));
+        self.current_pos = Some(pos);
+        self.path_left -= 1;
+    }
+
+    /// `perform_move` without the roll: modificators change and the hero moves,
+    /// but no points are added and the hero does not level up.
+    pub(crate) fn move_without_roll(&mut self, pos: TilePos, action: Option<Rc<Resource<ActionData>>>) {
+        if let Some(action) = &action {
+            self.apply_modificators(action);
+        }
+        self.advance(pos, action);
+    }
+
+    pub fn get_possible_decisions(&self, game: &STWGame) -> Vec<String> {
+        match game.quests.get(&self.quest_pos) {
+            Some(quest) => quest.get_quest_type().data.quest_decisions
+                .iter()
+                .filter(|e|self.is_decision_permited(e, &game.heroes[self.hero_index], &game.get_condition_facts()))
+                .map(|e|e.id.to_string())
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// `required` holds alternatives - decision is open when there are none
+    /// or when all requirements of at least one group are met.
+    pub fn is_decision_permited(&self, decision: &QuestDecision, hero: &Hero, facts: &ConditionFacts) -> bool {
+        self.check_decision(decision, hero, facts).is_ok()
+    }
+
+    /// `is_decision_permited` with the reason when it is not.
+    pub fn check_decision(&self, decision: &QuestDecision, hero: &Hero, facts: &ConditionFacts) -> Result<(), String> {
+        if decision.required.is_empty() {
+            return Ok(());
+        }
+        let mut failed = vec![];
+        for group in decision.required.iter() {
+            match check_all(group, facts, &|req|self.check_point_requirment(req, hero)) {
+                Ok(()) => return Ok(()),
+                Err(e) => failed.push(e),
+            }
+        }
+        Err(format!("none of: {}", failed.join(" | ")))
+    }
+
+    /// Points gathered for the decision: skill points weighted by
+    /// `required_skills` and one point for every met `additional_points` entry.
+    pub fn get_decision_points(&self, decision: &QuestDecision, hero: &Hero) -> f32 {
+        let mut skills: Vec<_> = decision.required_skills.iter().collect();
+        skills.sort_by_key(|e|*e.0);
+        let skill_points: f32 = skills
+            .into_iter()
+            .map(|e|e.1 * self.points_got.get(e.0).unwrap_or(&0.0))
+            .sum();
+        skill_points + decision.additional_points
+            .iter()
+            .filter(|req|self.meets_point_requirment(req, hero))
+            .count() as f32
+    }
+
+    fn check_point_requirment(&self, req: &PointRequirment, hero: &Hero) -> Result<(), String> {
+        match self.meets_point_requirment(req, hero) {
+            true => Ok(()),
+            false => Err(format!("{} is not met", req)),
+        }
+    }
+
+    fn meets_point_requirment(&self, req: &PointRequirment, hero: &Hero) -> bool {
+        match req {
+            PointRequirment::DidAction(id) =>
+                self.steps.iter().any(|e|e.1.as_ref().is_some_and(|v|v.id == *id)),
+            PointRequirment::DidActionFamily(family) =>
+                self.steps.iter().any(|e|e.1.as_ref().is_some_and(|v|v.data.action_family == *family)),
+            PointRequirment::HeroFromFamliy(family) => hero.get_background().data.hero_family == *family,
+        }
+    }
+
     
 }
\ No newline at end of file
@@ -1,12 +1,14 @@
 use std::{collections::{HashMap}, rc::{Rc}, ops::{Deref, DerefMut}};
 
 use crate::resource::{ResourceManager, resource_data::OriginFieldData, Resource};
-use colored::{Colorize, ColoredString};
+#[cfg(feature = "color")]
+use colored::Colorize;
 use noise::{Perlin};
 use noise::utils::{NoiseMapBuilder, PlaneMapBuilder};
 use rand::Rng;
 use rand_pcg::Pcg64;
 use rand_seeder::Seeder;
+use serde::{Deserialize, Serialize};
 use super::{tile::*, GameError};
 
 /**
@@ -63,28 +65,36 @@ impl GameMap {
     }
 
     pub fn print(&self){
-        let cords = GameMap::get_min_max_coords(self.size);
-        let mut lines = Vec::new();
-        for r in cords.0..cords.1 as i32{
-            let text: Vec<ColoredString> = (cords.2..cords.3)
-            .map(|q|{
-                self.tiles
-                    .get(&TilePos{q,r})
-                    .map_or_else(||"##".to_string().truecolor(255,255,255), |e|e.get_colored_string())
-                }
-            )
-            .collect();
-            lines.push(text)
-            
-        }
-        for l in lines.iter(){
-            for e in l.iter(){
-                print!("{}",e)
+        for row in self.get_rows(){
+            for (_, tile) in row.iter(){
+                print!("{}", GameMap::tile_label(*tile))
             }
             print!("\n")
         }
     }
 
+    #[cfg(feature = "color")]
+    fn tile_label(tile: Option<&GameTile>) -> String{
+        tile.map_or_else(||"##".to_string().truecolor(255,255,255), |e|e.get_colored_string()).to_string()
+    }
+
+    #[cfg(not(feature = "color"))]
+    fn tile_label(tile: Option<&GameTile>) -> String{
+        tile.map_or_else(||"##".to_string(), |e|e.get_short_name())
+    }
+
+    /// Map laid out the way `print` draws it - one row per `r`, cells for
+    /// positions without a tile are `None`.
+    pub fn get_rows(&self) -> Vec<Vec<(TilePos, Option<&GameTile>)>>{
+        let cords = GameMap::get_min_max_coords(self.size);
+        (cords.0..cords.1)
+            .map(|r|(cords.2..cords.3)
+                .map(|q|(TilePos{q,r}, self.tiles.get(&TilePos{q,r}).map(|e|e.as_ref())))
+                .collect()
+            )
+            .collect()
+    }
+
     pub fn get_adjacent_tiles(&self, pos: &TilePos) -> Vec<&GameTile> {
         pos.adjacent_positions().iter()
             .filter_map(|e|self.tiles.get(e).map(
@@ -139,16 +149,32 @@ impl GameMap {
             })
     }
 
+    /// Tiles ordered by position - use instead of `iter` wherever the order
+    /// matters for the outcome (random rolls), so games stay reproducible.
+    pub fn iter_sorted(&self) -> Vec<(&TilePos, &GameTile)> {
+        let mut res: Vec<(&TilePos, &GameTile)> = self.tiles.iter()
+            .map(|e|(e.0, e.1.as_ref()))
+            .collect();
+        res.sort_by_key(|e|*e.0);
+        res
+    }
+
     //priv
 
     fn find_proper_resource<'a>(btt: &HashMap<&'a str, Rc<Resource<OriginFieldData>>>, h: f32, v:f32, is_near: bool, enabled_near: &[String]) -> &'a str{
-        btt.iter()
+        // sorted, so ties and the fallback do not depend on HashMap order
+        let mut allowed: Vec<_> = btt.iter()
             .filter(|e| !is_near || enabled_near.contains(&e.0.to_string()))
-            .filter(|e|e.1.data.height > h && e.1.data.vegetation > v)
-            .map(|e|(e.0, e.1.data.height + e.1.data.vegetation))
-            .min_by(|a,b| a.1.total_cmp(&b.1))
-            .map(|e|*e.0)
-            .unwrap_or(btt.keys().next().unwrap())
+            .map(|e|(*e.0, e.1.data.height, e.1.data.vegetation))
+            .collect();
+        allowed.sort_by(|a, b|a.0.cmp(b.0));
+        allowed.iter()
+            .filter(|e|e.1 > h && e.2 > v)
+            .min_by(|a,b| (a.1 + a.2).total_cmp(&(b.1 + b.2)))
+            // noise above every origin gets the highest one
+            .or_else(||allowed.iter().max_by(|a,b| (a.1 + a.2).total_cmp(&(b.1 + b.2))))
+            .map(|e|e.0)
+            .unwrap_or_else(||*btt.keys().min().unwrap())
     }
 
     fn get_min_max_coords(size: u32) ->(i32,i32,i32,i32) {
@@ -208,7 +234,8 @@ impl DerefMut for GameMap {
 }
 
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+/// Axial hex coordinates. Serialized as `{"q": 8, "r": 18}`.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct TilePos{
     pub q: i32,
     pub r: i32,
@@ -0,0 +1,93 @@
+//! Exchanges of `ExchangeData`. An offer is open while the player owns a
+//! building on one of its paths and gets cheaper with every other one. Buying
+//! raises the price, which falls back over the following turns.
+
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::resource::{Resource, enums::GResource, resource_data::ExchangeData};
+
+use super::{STWGame, BadMove, game_controller::GameCallback};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MarketOffer{
+    pub id: String,
+    pub give: GResource,
+    pub take: GResource,
+    /// Units of `give` for one unit of `take` right now.
+    pub rate: f32,
+    pub open: bool
+}
+
+impl STWGame {
+    /// Every exchange, ordered by id.
+    pub fn get_market(&self) -> Vec<MarketOffer>{
+        let mut res: Vec<MarketOffer> = self.resource_manager.get_resources::<ExchangeData>().values()
+            .map(|e|MarketOffer {
+                id: e.id.to_string(),
+                give: e.data.give,
+                take: e.data.take,
+                rate: self.get_exchange_rate(e),
+                open: self.count_exchange_buildings(e) > 0
+            })
+            .collect();
+        res.sort_by(|a, b|a.id.cmp(&b.id));
+        res
+    }
+
+    /// Cost in `give` of `amount` of `take` on the cheapest open offer.
+    pub fn get_trade_cost(&self, give: GResource, take: GResource, amount: u32) -> Option<u32>{
+        self.find_exchange(give, take).map(|e|Self::cost(self.get_exchange_rate(&e), amount))
+    }
+
+    pub(super) fn trade(&mut self, give: GResource, take: GResource, amount: u32) -> Result<Vec<GameCallback>, BadMove>{
+        self.is_playing_history()?;
+        if amount == 0 {
+            return Err(BadMove::new("Can not trade nothing".to_string()));
+        }
+        let exchange = self.find_exchange(give, take)
+            .ok_or_else(||BadMove::new(format!("No open offer of {:?} for {:?}", take, give)))?;
+        let cost = Self::cost(self.get_exchange_rate(&exchange), amount);
+        let stock = *self.game_resources.get(&give).unwrap_or(&0);
+        if stock < cost {
+            return Err(BadMove::new(format!("Not enough {:?} - {} needed, {} owned", give, cost, stock)));
+        }
+
+        let before = self.game_resources.clone();
+        *self.game_resources.entry(give).or_insert(0) -= cost;
+        *self.game_resources.entry(take).or_insert(0) += amount;
+        *self.market_demand.entry(exchange.id.to_string()).or_insert(0.0) += exchange.data.demand_step * amount as f32;
+        Ok(self.get_changed_resource_callbacks(&before))
+    }
+
+    fn find_exchange(&self, give: GResource, take: GResource) -> Option<Rc<Resource<ExchangeData>>>{
+        let mut offers: Vec<_> = self.resource_manager.get_resources::<ExchangeData>().values()
+            .filter(|e|e.data.give == give && e.data.take == take && self.count_exchange_buildings(e) > 0)
+            .cloned()
+            .collect();
+        offers.sort_by(|a, b|self.get_exchange_rate(a).total_cmp(&self.get_exchange_rate(b)).then_with(||a.id.cmp(&b.id)));
+        offers.into_iter().next()
+    }
+
+    fn get_exchange_rate(&self, exchange: &Resource<ExchangeData>) -> f32{
+        let buildings = self.count_exchange_buildings(exchange).max(1);
+        let demand = self.market_demand.get(&exchange.id).unwrap_or(&0.0);
+        exchange.data.base_rate * exchange.data.building_discount.powi(buildings as i32 - 1) * (1.0 + demand)
+    }
+
+    /// Buildings on the required paths, 1 when the offer needs none.
+    fn count_exchange_buildings(&self, exchange: &Resource<ExchangeData>) -> u32{
+        if exchange.data.required_paths.is_empty() {
+            return 1;
+        }
+        self.map.iter_sorted().iter()
+            .filter_map(|e|e.1.get_field_content())
+            .filter(|e|exchange.data.required_paths.contains(&e.data.build_path))
+            .count() as u32
+    }
+
+    fn cost(rate: f32, amount: u32) -> u32{
+        ((rate * amount as f32).ceil() as u32).max(1)
+    }
+}
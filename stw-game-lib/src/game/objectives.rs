@@ -0,0 +1,67 @@
+//! Win and loss conditions from `Globals::objectives`. They are checked after
+//! every move, and once one is met the game is over and rejects moves.
+
+use serde::{Deserialize, Serialize};
+
+use crate::resource::enums::{LossCondition, WinCondition};
+
+use super::{STWGame, game_controller::GameCallback};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", content = "data")]
+pub enum GameOutcome {
+    Won{condition: WinCondition, score: u32},
+    Lost{condition: LossCondition, score: u32}
+}
+
+impl STWGame {
+    /// `None` while the game goes on.
+    pub fn get_outcome(&self) -> Option<&GameOutcome>{
+        self.outcome.as_ref()
+    }
+
+    pub fn is_game_over(&self) -> bool{
+        self.outcome.is_some()
+    }
+
+    /// Ends the game when a condition is met. Loss is checked first.
+    /// `pending` are the callbacks of the move just played.
+    pub(super) fn check_objectives(&mut self, pending: &[GameCallback]) -> Vec<GameCallback>{
+        if self.outcome.is_some() {
+            return vec![];
+        }
+        let globals = self.resource_manager.get_globals();
+        let objectives = &globals.data.objectives;
+        if let Some(condition) = objectives.loss.iter().find(|e|self.is_loss_met(e)) {
+            let score = self.summarize(pending, false).score;
+            self.outcome = Some(GameOutcome::Lost { condition: condition.clone(), score });
+            return vec![GameCallback::GameLost{condition: condition.clone(), score}];
+        }
+        if let Some(condition) = objectives.win.iter().find(|e|self.is_win_met(e)) {
+            let score = self.summarize(pending, true).score;
+            self.outcome = Some(GameOutcome::Won { condition: condition.clone(), score });
+            return vec![GameCallback::GameWon{condition: condition.clone(), score}];
+        }
+        vec![]
+    }
+
+    fn is_win_met(&self, condition: &WinCondition) -> bool{
+        match condition {
+            WinCondition::PathLevel { build_path, level } => self.map.iter_sorted().iter()
+                .filter_map(|e|e.1.get_field_content())
+                .any(|e|e.data.build_path == *build_path && e.data.path_level >= *level),
+            WinCondition::Collect { resource, amount } => self.game_resources.get(resource).unwrap_or(&0) >= amount,
+            WinCondition::QuestsCompleted(amount) => self.quests_completed >= *amount,
+            WinCondition::SurviveTurns(turns) => self.game_turn >= *turns,
+        }
+    }
+
+    fn is_loss_met(&self, condition: &LossCondition) -> bool{
+        match condition {
+            LossCondition::AllHeroesDead => self.heroes.is_empty() && self.heroes_died > 0,
+            LossCondition::MiddleDestroyed => self.map.get(&self.map.get_mid_position())
+                .is_some_and(|e|e.get_field_content().is_none()),
+            LossCondition::TurnLimit(limit) => self.game_turn > *limit,
+        }
+    }
+}
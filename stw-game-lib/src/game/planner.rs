@@ -0,0 +1,144 @@
+//! Search for hero routes that give a quest decision the best chance. Routes
+//! follow the same rules as `History::get_possible_next_move`, so every
+//! returned route can be played move by move. The same search ranks heroes
+//! for a quest.
+
+use std::{cmp::Ordering, collections::BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::resource::resource_data::QuestDecision;
+
+use super::{STWGame, BadMove, map::TilePos, history::History, preview::DecisionPreview};
+
+/// Number of partial routes kept after every step of the search.
+pub const PLANNER_BEAM: usize = 256;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PlannedRoute{
+    /// `(pos, action)` moves, as in `GameMove::PlayMove`.
+    pub moves: Vec<(TilePos, String)>,
+    pub decision: DecisionPreview
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HeroRecommendation{
+    pub hero_index: usize,
+    /// Decision the hero has the best chance for.
+    pub decision: String,
+    pub route: PlannedRoute
+}
+
+#[derive(Clone)]
+struct PartialRoute{
+    history: History,
+    moves: Vec<(TilePos, String)>,
+    rolls: Vec<(f32, f32)>,
+    expected: f32
+}
+
+impl STWGame {
+    /// Up to `k` best routes of `hero_index` for `decision` of the quest at
+    /// `quest_pos`, best first. Continues the current history when it is
+    /// played by that hero on that quest, plans from the start otherwise.
+    /// The empty route is a candidate too - it means deciding right away.
+    pub fn plan_routes(&self, quest_pos: &TilePos, hero_index: usize, decision: &str, k: usize) -> Result<Vec<PlannedRoute>, BadMove>{
+        let history = match &self.history {
+            Some(history) if history.quest_pos == *quest_pos && history.hero_index == hero_index => history.clone(),
+            Some(_) => return Err(BadMove::new("Game is playing another history".to_string())),
+            None => History::new(quest_pos, hero_index, self)?,
+        };
+        let decision = self.find_decision(&history, decision)?;
+        Ok(self.plan_from(history, decision, k))
+    }
+
+    /// Best route for `decision` in the current history, if any.
+    pub fn best_route(&self, decision: &str) -> Option<PlannedRoute>{
+        let history = self.history.as_ref()?;
+        self.plan_routes(&history.quest_pos, history.hero_index, decision, 1).ok()?.pop()
+    }
+
+    /// Heroes that can take the quest at `pos`, best first, each with the
+    /// decision it has the best chance for and the route to it. Skills,
+    /// hero family and decision requirements are all taken into account by
+    /// planning the routes. Works also while another history is played.
+    pub fn rank_heroes_for_quest(&self, pos: &TilePos) -> Result<Vec<HeroRecommendation>, BadMove>{
+        let quest = self.quests.get(pos)
+            .ok_or_else(||BadMove::new(format!("No quest at position {:?}", pos)))?;
+        let mut res = vec![];
+        for hero_index in 0..self.heroes.len() {
+            let Ok(history) = History::new(pos, hero_index, self) else {
+                continue;
+            };
+            let best = quest.get_quest_type().data.quest_decisions.iter()
+                .filter_map(|decision|self.plan_from(history.clone(), decision, 1).pop().map(|e|(decision.id.to_string(), e)))
+                .fold(None, |best: Option<(String, PlannedRoute)>, e|match best {
+                    Some(b) if compare_routes(&b.1, &e.1) != Ordering::Greater => Some(b),
+                    _ => Some(e),
+                });
+            if let Some((decision, route)) = best {
+                res.push(HeroRecommendation { hero_index, decision, route });
+            }
+        }
+        res.sort_by(|a, b|compare_routes(&a.route, &b.route).then_with(||a.hero_index.cmp(&b.hero_index)));
+        Ok(res)
+    }
+
+    /// Up to `k` best routes continuing `history`.
+    fn plan_from(&self, history: History, decision_data: &QuestDecision, k: usize) -> Vec<PlannedRoute>{
+        let hero = &self.heroes[history.hero_index];
+
+        let root = PartialRoute { expected: history.get_decision_points(decision_data, hero), history, moves: vec![], rolls: vec![] };
+        let mut candidates = vec![root.clone()];
+        let mut layer = vec![root];
+        while !layer.is_empty() {
+            let mut next = vec![];
+            for route in layer.iter().filter(|e|e.history.path_left > 0) {
+                for (pos, action) in route.history.get_possible_next_move(self) {
+                    let mut step = route.clone();
+                    let roll = self.move_for_preview(&mut step.history, decision_data, pos, &action);
+                    step.expected = step.history.get_decision_points(decision_data, hero)
+                        + step.rolls.iter().chain(roll.iter()).map(|e|e.0 + e.1 / 2.0).sum::<f32>();
+                    step.rolls.extend(roll);
+                    step.moves.push((pos, action));
+                    next.push(step);
+                }
+            }
+            next.sort_by(|a, b|b.expected.total_cmp(&a.expected).then_with(||a.moves.cmp(&b.moves)));
+            next.truncate(PLANNER_BEAM);
+            candidates.extend(next.iter().cloned());
+            layer = next;
+        }
+
+        // cheap ranking first, the chance is computed only for the best ones
+        let facts = self.get_condition_facts();
+        let heuristic = |route: &PartialRoute|if route.history.is_decision_permited(decision_data, hero, &facts) {
+            decision_data.success_probability(route.expected)
+        } else {
+            -1.0
+        };
+        candidates.sort_by(|a, b|heuristic(b).total_cmp(&heuristic(a))
+            .then_with(||b.expected.total_cmp(&a.expected))
+            .then_with(||a.moves.len().cmp(&b.moves.len()))
+            .then_with(||a.moves.cmp(&b.moves)));
+        candidates.truncate(k.saturating_mul(4));
+
+        let mut routes: Vec<PlannedRoute> = candidates.into_iter()
+            .map(|e|PlannedRoute { decision: self.evaluate_decision(&e.history, decision_data, &e.rolls, &e.moves), moves: e.moves })
+            .collect();
+        routes.sort_by(compare_routes);
+        // the same moves in another order are not a different route
+        let mut seen = BTreeSet::new();
+        routes.retain(|e|seen.insert(e.moves.iter().cloned().collect::<BTreeSet<_>>()));
+        routes.truncate(k);
+        routes
+    }
+}
+
+/// Better routes first: higher chance, more expected points, fewer moves.
+fn compare_routes(a: &PlannedRoute, b: &PlannedRoute) -> Ordering{
+    b.decision.success_probability.total_cmp(&a.decision.success_probability)
+        .then_with(||b.decision.points.expected.total_cmp(&a.decision.points.expected))
+        .then_with(||a.moves.len().cmp(&b.moves.len()))
+        .then_with(||a.moves.cmp(&b.moves))
+}
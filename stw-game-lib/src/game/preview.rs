@@ -0,0 +1,178 @@
+//! What an action or a decision is likely to give, without touching the game
+//! rng. An action rolls one `rand_succes` for all its points, so its points
+//! are uniform between the values for rolls 0 and 1 and rise together.
+
+use std::collections::BTreeMap;
+
+use rand::Rng;
+use rand_pcg::Pcg64;
+use rand_seeder::Seeder;
+use serde::{Deserialize, Serialize};
+
+use crate::resource::{enums::HeroSkill, resource_data::{ActionData, QuestDecision}};
+
+use super::{STWGame, BadMove, map::TilePos, history::History};
+
+/// Number of seeded samples used when the chance can not be computed exactly.
+pub const PREVIEW_SAMPLES: u32 = 10000;
+
+/// Points uniformly distributed between `min` and `max`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct PointsRange{
+    pub min: f32,
+    pub max: f32,
+    pub expected: f32
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ActionPreview{
+    pub points: BTreeMap<HeroSkill, PointsRange>,
+    /// `(before, after)` of every modificator the action changes.
+    pub modificators: BTreeMap<HeroSkill, (f32, f32)>,
+    /// Chance that the hero levels up by `eternal_skills` - it happens for
+    /// the highest rolls, so together with the most points.
+    pub eternal_probability: f32,
+    pub eternal_skills: BTreeMap<HeroSkill, f32>
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DecisionPreview{
+    /// `required` is met after the path, otherwise the chance is 0.
+    pub permitted: bool,
+    pub points: PointsRange,
+    pub success_probability: f32,
+    /// `false` when `success_probability` comes from `PREVIEW_SAMPLES` samples.
+    pub exact: bool
+}
+
+/// Mean of `success_probability` over points uniform in `[lo, hi]`, using
+/// the antiderivative `x - p50 * ln(x + p50)` of `x / (x + p50)`.
+fn mean_success_probability(decision: &QuestDecision, lo: f32, hi: f32) -> f32{
+    let (lo, hi) = (lo.min(hi) as f64, lo.max(hi) as f64);
+    if hi - lo < 1e-9 {
+        return decision.success_probability(lo as f32);
+    }
+    let from = lo.max(decision.min_required_points as f64).max(0.0);
+    if from >= hi {
+        return 0.0;
+    }
+    let p = decision.points_50p as f64;
+    let integral = |x: f64|if p == 0.0 { x } else { x - p * (x + p).ln() };
+    ((integral(hi) - integral(from)) / (hi - lo)) as f32
+}
+
+impl STWGame {
+    /// Points, modificator changes and level up chance of doing `action` at
+    /// `pos` as the next move of the current history.
+    pub fn preview_action(&self, pos: &TilePos, action: &str) -> Result<ActionPreview, BadMove>{
+        let history = self.history.as_ref()
+            .ok_or_else(||BadMove::new("Game is not playing history".to_string()))?;
+        if !self.get_possible_hero_moves().contains(&(*pos, action.to_string())) {
+            return Err(BadMove::new(format!("Move hero to pos {:?} and do action {} is inpossible", pos, action)));
+        }
+        let Ok(action) = self.resource_manager.get_resource::<ActionData>(action) else {
+            // walking through a tile without doing anything
+            return Ok(ActionPreview { points: BTreeMap::new(), modificators: BTreeMap::new(), eternal_probability: 0.0, eternal_skills: BTreeMap::new() });
+        };
+
+        let mut low: BTreeMap<HeroSkill, f32> = BTreeMap::new();
+        let mut high: BTreeMap<HeroSkill, f32> = BTreeMap::new();
+        for (skill, points) in history.roll_points(&action, 0.0) {
+            *low.entry(skill).or_insert(0.0) += points;
+        }
+        for (skill, points) in history.roll_points(&action, 1.0) {
+            *high.entry(skill).or_insert(0.0) += points;
+        }
+        let points = low.into_iter()
+            .map(|(skill, lo)|{
+                let hi = high[&skill];
+                (skill, PointsRange { min: lo.min(hi), max: lo.max(hi), expected: (lo + hi) / 2.0 })
+            })
+            .collect();
+        let modificators = action.data.modificators.iter()
+            .filter_map(|e|history.current_modificators.get(e.0).map(|v|(*e.0, (*v, v + e.1))))
+            .collect();
+        let hero_skills = self.heroes[history.hero_index].get_skills();
+        Ok(ActionPreview {
+            points,
+            modificators,
+            eternal_probability: action.data.eternal_modificator.probability.clamp(0.0, 1.0),
+            eternal_skills: action.data.eternal_modificator.skills.iter()
+                .filter(|e|hero_skills.contains_key(e.0))
+                .map(|e|(*e.0, *e.1))
+                .collect()
+        })
+    }
+
+    /// Chance that `decision` succeeds after the hero of the current history
+    /// also walks `path` (`(pos, action)` moves, may be empty). Exact when at
+    /// most one move of the path rolls points, sampled with a seed taken from
+    /// the arguments otherwise.
+    pub fn decision_success_probability(&self, decision: &str, path: &[(TilePos, String)]) -> Result<DecisionPreview, BadMove>{
+        let history = self.history.as_ref()
+            .ok_or_else(||BadMove::new("Game is not playing history".to_string()))?;
+        let decision_data = self.find_decision(history, decision)?;
+
+        let mut history = history.clone();
+        let mut rolls = vec![];
+        for (pos, action) in path {
+            if history.path_left == 0 || !history.get_possible_next_move(self).contains(&(*pos, action.to_string())) {
+                return Err(BadMove::new(format!("Move hero to pos {:?} and do action {} is inpossible", pos, action)));
+            }
+            rolls.extend(self.move_for_preview(&mut history, decision_data, *pos, action));
+        }
+        Ok(self.evaluate_decision(&history, decision_data, &rolls, path))
+    }
+
+    pub(super) fn find_decision<'a>(&'a self, history: &History, decision: &str) -> Result<&'a QuestDecision, BadMove>{
+        let quest = self.quests.get(&history.quest_pos)
+            .ok_or_else(||BadMove::new(format!("No quest at position {:?}", history.quest_pos)))?;
+        quest.get_decision(decision)
+            .ok_or_else(||BadMove::new(format!("Quest {} has no decision {}", quest.get_quest_type().id, decision)))
+    }
+
+    /// Moves the hero without rolling. Returns the decision points the move
+    /// gives as `(points for roll 0, extra points for roll 1)`.
+    pub(super) fn move_for_preview(&self, history: &mut History, decision: &QuestDecision, pos: TilePos, action: &str) -> Option<(f32, f32)>{
+        let action = self.resource_manager.get_resource::<ActionData>(action).ok();
+        let res = action.as_ref().map(|action|{
+            let weighted = |u: f32|history.roll_points(action, u).into_iter()
+                .map(|e|decision.required_skills.get(&e.0).unwrap_or(&0.0) * e.1)
+                .sum::<f32>();
+            let (lo, hi) = (weighted(0.0), weighted(1.0));
+            (lo, hi - lo)
+        });
+        history.move_without_roll(pos, action);
+        res
+    }
+
+    /// Chance of `decision` after `history`, which walked `path` with the
+    /// given rolls since the real history.
+    pub(super) fn evaluate_decision(&self, history: &History, decision: &QuestDecision, rolls: &[(f32, f32)], path: &[(TilePos, String)]) -> DecisionPreview{
+        let hero = &self.heroes[history.hero_index];
+        let base = history.get_decision_points(decision, hero) + rolls.iter().map(|e|e.0).sum::<f32>();
+        let rolls: Vec<f32> = rolls.iter().map(|e|e.1).filter(|e|*e != 0.0).collect();
+        let (min, max) = rolls.iter()
+            .fold((base, base), |acc, r|(acc.0 + r.min(0.0), acc.1 + r.max(0.0)));
+        let points = PointsRange { min, max, expected: base + rolls.iter().sum::<f32>() / 2.0 };
+
+        if !history.is_decision_permited(decision, hero, &self.get_condition_facts()) {
+            return DecisionPreview { permitted: false, points, success_probability: 0.0, exact: true };
+        }
+        let (success_probability, exact) = match rolls.as_slice() {
+            [] => (decision.success_probability(base), true),
+            [roll] => (mean_success_probability(decision, base, base + roll), true),
+            rolls => {
+                let mut rng: Pcg64 = Seeder::from((decision.id.as_str(), format!("{:?}", path), "preview")).make_rng();
+                let sum: f64 = (0..PREVIEW_SAMPLES)
+                    .map(|_|{
+                        let x = base + rolls.iter().map(|r|r * rng.gen::<f32>()).sum::<f32>();
+                        decision.success_probability(x) as f64
+                    })
+                    .sum();
+                ((sum / PREVIEW_SAMPLES as f64) as f32, false)
+            },
+        };
+        DecisionPreview { permitted: true, points, success_probability, exact }
+    }
+}
@@ -1,6 +1,6 @@
 use std::{rc::Rc, fmt::Debug};
 
-use crate::resource::{resource_data::QuestData, Resource, ResourceManager};
+use crate::resource::{resource_data::{QuestData, QuestDecision}, Resource, ResourceManager};
 
 use super::{map::TilePos, GameError};
 
@@ -36,5 +36,17 @@ impl Quest{
 
     pub fn get_position(&self) -> TilePos{
         self.position
-    } 
+    }
+
+    pub fn get_quest_type(&self) -> Rc<Resource<QuestData>>{
+        self.quest_type.clone()
+    }
+
+    pub(crate) fn get_decision(&self, id: &str) -> Option<&QuestDecision>{
+        self.quest_type.data.quest_decisions.iter().find(|e|e.id == id)
+    }
+
+    pub fn get_creation_turn(&self) -> u32{
+        self.creation_turn
+    }
 }
\ No newline at end of file
@@ -0,0 +1,122 @@
+use std::{fmt::Display, fs};
+
+use serde::{Deserialize, Serialize};
+
+use crate::resource::resources_hash;
+
+use super::{GameConfig, STWGame, GameError, BadMove, game_move::GameMove, game_controller::GameCallback};
+
+const REPLAY_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReplayStep{
+    pub game_move: GameMove,
+    pub callbacks: Vec<GameCallback>
+}
+
+/// Full record of a game. All random rolls come from a generator seeded with
+/// `seed`, so the same moves played on the same resources reproduce the game
+/// exactly.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Replay{
+    pub version: u32,
+    pub name: String,
+    pub seed: String,
+    pub map_size: u32,
+    pub resources_hash: String,
+    pub steps: Vec<ReplayStep>
+}
+
+#[derive(Debug, Clone)]
+pub enum ReplayError{
+    Game(GameError),
+    Divergence{step: usize, game_move: GameMove, expected: Vec<GameCallback>, actual: Result<Vec<GameCallback>, BadMove>}
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::Game(e) => write!(f, "{}", e),
+            ReplayError::Divergence { step, game_move, expected, actual } =>
+                write!(f, "Replay diverged at step {} ({:?}): expected {:?}, got {:?}", step, game_move, expected, actual),
+        }
+    }
+}
+
+impl From<GameError> for ReplayError {
+    fn from(e: GameError) -> Self {
+        ReplayError::Game(e)
+    }
+}
+
+impl Replay {
+    pub fn new(name: &str, seed: &str, map_size: u32, resources_hash: &str) -> Replay{
+        Replay {
+            version: REPLAY_VERSION,
+            name: name.to_string(),
+            seed: seed.to_string(),
+            map_size,
+            resources_hash: resources_hash.to_string(),
+            steps: Vec::new()
+        }
+    }
+
+    pub fn load_from_file(path: &str) -> Result<Replay, GameError>{
+        let text = fs::read_to_string(path)
+            .map_err(|e|GameError::new(format!("Can not read replay file {}: {}", path, e)))?;
+        Replay::from_json(&text)
+            .map_err(|e|GameError::new(format!("{} in {}", e.msg, path)))
+    }
+
+    pub fn save_to_file(&self, path: &str) -> Result<(), GameError>{
+        fs::write(path, self.to_json()?)
+            .map_err(|e|GameError::new(format!("Can not write replay file {}: {}", path, e)))
+    }
+
+    pub fn from_json(text: &str) -> Result<Replay, GameError>{
+        let replay: Replay = serde_json::from_str(text)
+            .map_err(|e|GameError::new(format!("Can not parse replay: {}", e)))?;
+        if replay.version != REPLAY_VERSION {
+            return Err(GameError::new(format!("Unsupported replay version {}", replay.version)));
+        }
+        Ok(replay)
+    }
+
+    pub fn to_json(&self) -> Result<String, GameError>{
+        serde_json::to_string_pretty(self)
+            .map_err(|e|GameError::new(format!("Can not serialize replay: {}", e)))
+    }
+
+    /// Rebuilds the game move by move and checks that every step produces the
+    /// recorded callbacks. Stops at the first step that differs.
+    pub fn play(&self, resources: Vec<(String, serde_json::Value)>) -> Result<STWGame, ReplayError>{
+        let hash = resources_hash(&resources);
+        if hash != self.resources_hash {
+            return Err(GameError::new(format!("Replay recorded with resources {} but played with {}", self.resources_hash, hash)).into());
+        }
+
+        let mut game = GameConfig::new()
+            .set_resources(resources)
+            .set_name(&self.name)
+            .set_seed(&self.seed)
+            .set_map_size(self.map_size)
+            .start_game()?;
+
+        for (step, recorded) in self.steps.iter().enumerate() {
+            let actual = game.perform_move(&recorded.game_move);
+            if actual.as_ref() != Ok(&recorded.callbacks) {
+                return Err(ReplayError::Divergence {
+                    step,
+                    game_move: recorded.game_move.clone(),
+                    expected: recorded.callbacks.clone(),
+                    actual
+                });
+            }
+        }
+        Ok(game)
+    }
+
+    pub(crate) fn record(&mut self, game_move: &GameMove, callbacks: &[GameCallback]){
+        self.steps.push(ReplayStep { game_move: game_move.clone(), callbacks: callbacks.to_vec() });
+    }
+}
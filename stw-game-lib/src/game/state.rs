@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::resource::enums::{GResource, HeroSkill};
+
+use super::{STWGame, map::TilePos, tile::GameTile, hero::Hero, objectives::GameOutcome};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TileState{
+    pub position: TilePos,
+    pub origin: String,
+    pub content: Option<String>,
+    pub visible: bool,
+    pub quest_completed: bool,
+    pub active: bool,
+    /// Building under construction, see `STWGame::get_constructions`.
+    pub construction: Option<String>
+}
+
+impl TileState {
+    pub(crate) fn new(tile: &GameTile) -> TileState{
+        TileState {
+            position: tile.get_position(),
+            origin: tile.get_base_field_type().id.to_string(),
+            content: tile.get_field_content().map(|c|c.id.to_string()),
+            visible: tile.is_visible(),
+            quest_completed: tile.get_quest_completed_till_last_build(),
+            active: tile.is_active(),
+            construction: tile.get_construction().map(|c|c.id.to_string())
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HeroState{
+    pub id: String,
+    pub name: String,
+    pub family: String,
+    pub skills: BTreeMap<HeroSkill, f32>
+}
+
+impl HeroState {
+    pub(crate) fn new(hero: &Hero) -> HeroState{
+        HeroState {
+            id: hero.get_background().id.to_string(),
+            name: hero.get_name().to_string(),
+            family: hero.get_background().data.hero_family.to_string(),
+            skills: hero.get_skills().iter().map(|s|(*s.0, *s.1)).collect()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct QuestState{
+    pub position: TilePos,
+    pub id: String,
+    pub creation_turn: u32
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HistoryState{
+    pub hero_index: usize,
+    pub quest_pos: TilePos,
+    pub current_pos: Option<TilePos>,
+    pub path_left: u32,
+    pub steps: Vec<(TilePos, String)>,
+    pub points_got: BTreeMap<HeroSkill, f32>,
+    pub current_modificators: BTreeMap<HeroSkill, f32>
+}
+
+/// Read-only snapshot of a game, part of the JSON protocol. Maps are ordered
+/// and lists sorted by position, so equal games give equal json.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GameState{
+    pub name: String,
+    pub turn: u32,
+    pub map_size: u32,
+    pub map_middle: TilePos,
+    pub resources: BTreeMap<GResource, u32>,
+    pub max_heroes: u32,
+    pub heroes: Vec<HeroState>,
+    pub quests: Vec<QuestState>,
+    pub history: Option<HistoryState>,
+    pub outcome: Option<GameOutcome>,
+    pub tiles: Vec<TileState>
+}
+
+impl GameState {
+    pub(crate) fn new(game: &STWGame) -> GameState{
+        let mut quests: Vec<QuestState> = game.quests.iter()
+            .map(|e|QuestState {
+                position: *e.0,
+                id: e.1.get_quest_type().id.to_string(),
+                creation_turn: e.1.get_creation_turn()
+            })
+            .collect();
+        quests.sort_by_key(|e|e.position);
+
+        GameState {
+            name: game.replay.name.to_string(),
+            turn: game.game_turn,
+            map_size: game.replay.map_size,
+            map_middle: game.map.get_mid_position(),
+            resources: game.game_resources.iter().map(|e|(*e.0, *e.1)).collect(),
+            max_heroes: game.max_heroes,
+            heroes: game.heroes.iter().map(|e|HeroState::new(e)).collect(),
+            quests,
+            history: game.history.as_ref().map(|e|HistoryState {
+                hero_index: e.hero_index,
+                quest_pos: e.quest_pos,
+                current_pos: e.current_pos,
+                path_left: e.path_left,
+                steps: e.steps.iter()
+                    .map(|s|(s.0, s.1.as_ref().map_or(String::new(), |a|a.id.to_string())))
+                    .collect(),
+                points_got: e.points_got.iter().map(|s|(*s.0, *s.1)).collect(),
+                current_modificators: e.current_modificators.iter().map(|s|(*s.0, *s.1)).collect()
+            }),
+            outcome: game.outcome.clone(),
+            tiles: game.map.iter_sorted()
+                .into_iter()
+                .map(|e|TileState::new(e.1))
+                .collect()
+        }
+    }
+}
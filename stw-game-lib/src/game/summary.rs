@@ -0,0 +1,180 @@
+//! End of game report. Everything except the final heroes and resources is
+//! read from the replay, so it is the same for a game and its playback.
+
+use std::{collections::{BTreeMap, HashMap}, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::resource::enums::GResource;
+
+use super::{STWGame, GameError, map::TilePos, game_move::GameMove, game_controller::GameCallback, objectives::GameOutcome, state::HeroState};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BuildRecord{
+    pub turn: u32,
+    pub position: TilePos,
+    pub id: String
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct QuestRecord{
+    pub turn: u32,
+    pub position: TilePos,
+    pub id: String,
+    pub decision: String
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GameSummary{
+    pub name: String,
+    pub seed: String,
+    pub turns: u32,
+    pub outcome: Option<GameOutcome>,
+    pub buildings: Vec<BuildRecord>,
+    pub quests_completed: Vec<QuestRecord>,
+    pub quests_failed: Vec<QuestRecord>,
+    pub heroes_recruited: u32,
+    pub heroes_lost: u32,
+    pub resources_earned: BTreeMap<GResource, u32>,
+    pub resources_spent: BTreeMap<GResource, u32>,
+    pub final_resources: BTreeMap<GResource, u32>,
+    /// Heroes alive at the end.
+    pub heroes: Vec<HeroState>,
+    /// Part of the score given by every nonzero `Scoring` weight.
+    pub score_parts: BTreeMap<String, f32>,
+    pub score: u32
+}
+
+impl GameSummary {
+    pub fn to_json(&self) -> Result<String, GameError>{
+        serde_json::to_string_pretty(self)
+            .map_err(|e|GameError::new(format!("Can not serialize summary: {}", e)))
+    }
+}
+
+fn join<T, F: Fn(&T) -> String>(items: impl IntoIterator<Item = T>, f: F) -> String{
+    let res: Vec<String> = items.into_iter().map(|e|f(&e)).collect();
+    if res.is_empty() { "-".to_string() } else { res.join(", ") }
+}
+
+impl Display for GameSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let outcome = match &self.outcome {
+            Some(GameOutcome::Won { condition, .. }) => format!("won ({:?})", condition),
+            Some(GameOutcome::Lost { condition, .. }) => format!("lost ({:?})", condition),
+            None => "not finished".to_string(),
+        };
+        writeln!(f, "Game {} (seed {}) - {}", self.name, self.seed, outcome)?;
+        writeln!(f, "Turns: {}", self.turns)?;
+        writeln!(f, "Score: {} ({})", self.score, join(&self.score_parts, |e|format!("{} {:.1}", e.0, e.1)))?;
+        writeln!(f, "Buildings: {}", self.buildings.len())?;
+        for building in self.buildings.iter() {
+            writeln!(f, "  turn {}: {} at ({},{})", building.turn, building.id, building.position.q, building.position.r)?;
+        }
+        writeln!(f, "Quests: {} completed, {} failed", self.quests_completed.len(), self.quests_failed.len())?;
+        for (quest, result) in self.quests_completed.iter().map(|e|(e, "success")).chain(self.quests_failed.iter().map(|e|(e, "failure"))) {
+            writeln!(f, "  turn {}: {} at ({},{}) - {}: {}", quest.turn, quest.id, quest.position.q, quest.position.r, quest.decision, result)?;
+        }
+        writeln!(f, "Heroes: {} recruited, {} lost", self.heroes_recruited, self.heroes_lost)?;
+        writeln!(f, "Resources earned: {}", join(&self.resources_earned, |e|format!("{:?} {}", e.0, e.1)))?;
+        writeln!(f, "Resources spent: {}", join(&self.resources_spent, |e|format!("{:?} {}", e.0, e.1)))?;
+        writeln!(f, "Final resources: {}", join(self.final_resources.iter().filter(|e|*e.1 > 0), |e|format!("{:?} {}", e.0, e.1)))?;
+        for hero in self.heroes.iter() {
+            writeln!(f, "Hero {} ({}): {}", hero.name, hero.id, join(&hero.skills, |e|format!("{:?} {:.2}", e.0, e.1)))?;
+        }
+        Ok(())
+    }
+}
+
+impl STWGame {
+    pub fn get_summary(&self) -> GameSummary{
+        self.summarize(&[], matches!(self.outcome, Some(GameOutcome::Won { .. })))
+    }
+
+    /// Score as weighted by `Globals::scoring`, see `GameSummary::score_parts`.
+    pub fn get_score(&self) -> u32{
+        self.get_summary().score
+    }
+
+    /// Summary of the replay followed by `pending` callbacks of the move
+    /// being played.
+    pub(super) fn summarize(&self, pending: &[GameCallback], won: bool) -> GameSummary{
+        let globals = self.resource_manager.get_globals();
+        let mut res = GameSummary {
+            name: self.replay.name.to_string(),
+            seed: self.replay.seed.to_string(),
+            turns: self.game_turn,
+            outcome: self.outcome.clone(),
+            buildings: vec![],
+            quests_completed: vec![],
+            quests_failed: vec![],
+            heroes_recruited: 0,
+            heroes_lost: 0,
+            resources_earned: BTreeMap::new(),
+            resources_spent: BTreeMap::new(),
+            final_resources: self.game_resources.iter().map(|e|(*e.0, *e.1)).collect(),
+            heroes: self.heroes.iter().map(|e|HeroState::new(e)).collect(),
+            score_parts: BTreeMap::new(),
+            score: 0
+        };
+
+        let mut resources: BTreeMap<GResource, u32> = GResource::iter()
+            .map(|e|(e, *globals.data.start_game_resources.get(&e).unwrap_or(&0)))
+            .collect();
+        let mut turn = 0;
+        let steps = self.replay.steps.iter()
+            .map(|e|(matches!(e.game_move, GameMove::Wait), e.callbacks.as_slice()))
+            .chain([(false, pending)]);
+        for (is_wait, callbacks) in steps {
+            if is_wait {
+                turn += 1;
+            }
+            for callback in callbacks {
+                match callback {
+                    GameCallback::ChangedResource { resource, new_value } => {
+                        let old = resources.insert(*resource, *new_value).unwrap_or(0);
+                        if *new_value > old {
+                            *res.resources_earned.entry(*resource).or_insert(0) += new_value - old;
+                        } else {
+                            *res.resources_spent.entry(*resource).or_insert(0) += old - new_value;
+                        }
+                    },
+                    GameCallback::NewTileContent { position, field_type_id } =>
+                        res.buildings.push(BuildRecord { turn, position: *position, id: field_type_id.to_string() }),
+                    GameCallback::QuestFinished { quest_pos, quest_id, decision, success } => {
+                        let record = QuestRecord { turn, position: *quest_pos, id: quest_id.to_string(), decision: decision.to_string() };
+                        if *success { res.quests_completed.push(record) } else { res.quests_failed.push(record) }
+                    },
+                    GameCallback::NewHero { .. } => res.heroes_recruited += 1,
+                    GameCallback::HeroDied { .. } => res.heroes_lost += 1,
+                    _ => {},
+                }
+            }
+        }
+
+        let scoring = &globals.data.scoring;
+        let weighted = |weights: &HashMap<GResource, f32>, amounts: &BTreeMap<GResource, u32>|weights.iter()
+            .map(|e|e.1 * *amounts.get(e.0).unwrap_or(&0) as f32)
+            .sum::<f32>();
+        let skills: f32 = res.heroes.iter().flat_map(|e|e.skills.values()).sum();
+        let parts = [
+            ("resources", weighted(&scoring.resources, &res.final_resources)),
+            ("resources_earned", weighted(&scoring.resources_earned, &res.resources_earned)),
+            ("buildings", scoring.building * res.buildings.len() as f32),
+            ("quests_completed", scoring.quest_completed * res.quests_completed.len() as f32),
+            ("quests_failed", scoring.quest_failed * res.quests_failed.len() as f32),
+            ("heroes_recruited", scoring.hero_recruited * res.heroes_recruited as f32),
+            ("heroes_lost", scoring.hero_lost * res.heroes_lost as f32),
+            ("skills", scoring.skill * skills),
+            ("turns", scoring.turn * res.turns as f32),
+            ("win", if won { scoring.win } else { 0.0 }),
+        ];
+        res.score_parts = parts.into_iter()
+            .filter(|e|e.1 != 0.0)
+            .map(|e|(e.0.to_string(), e.1))
+            .collect();
+        res.score = res.score_parts.values().sum::<f32>().max(0.0).round() as u32;
+        res
+    }
+}
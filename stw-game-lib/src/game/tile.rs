@@ -1,8 +1,9 @@
-use std::{rc::{Rc}, fmt::Debug};
+use std::{rc::{Rc}, fmt::Debug, collections::HashMap};
 
+#[cfg(feature = "color")]
 use colored::{ColoredString, Colorize};
 
-use crate::resource::{ResourceManager, Resource, resource_data::{OriginFieldData, FieldTypeData}};
+use crate::resource::{ResourceManager, Resource, enums::GResource, resource_data::{OriginFieldData, FieldTypeData}};
 
 use super::{map::{TilePos}, GameError};
 
@@ -12,6 +13,16 @@ pub struct GameTile{
     field_content: Option<Rc<Resource<FieldTypeData>>>,
     visible: bool,
     get_quest_completed_till_last_build: bool,
+    active: bool,
+    construction: Option<Rc<Resource<FieldTypeData>>>,
+    /// Costs paid for the content and for the lower levels of its path built
+    /// here before it, the content last.
+    paid_costs: Vec<HashMap<GResource, u32>>,
+    /// Contents the current one was upgraded from, the last one right below it.
+    upgraded_from: Vec<Rc<Resource<FieldTypeData>>>,
+    /// Whether the content was the first of its kind built in the game, so
+    /// its effects played only the first time were played here.
+    first_built: bool,
 
     resource_manager: Rc<ResourceManager>,
 }
@@ -25,7 +36,12 @@ impl GameTile {
             resource_manager: rm.clone(),
             field_content: None,
             visible: visible,
-            get_quest_completed_till_last_build: false
+            get_quest_completed_till_last_build: false,
+            active: true,
+            construction: None,
+            paid_costs: Vec::new(),
+            upgraded_from: Vec::new(),
+            first_built: false
         })
     }
 
@@ -52,23 +68,112 @@ impl GameTile {
     pub fn set_field_content(&mut self, id: &str)-> Result<(), GameError>{
         self.field_content = Some(self.resource_manager.get_resource(id)?.clone());
         self.get_quest_completed_till_last_build = false;
+        self.active = true;
+        self.construction = None;
+        self.first_built = false;
         Ok(())
     }
 
+    /// Building under construction here. The current content stays until
+    /// it is finished.
+    pub fn get_construction(&self)->Option<Rc<Resource<FieldTypeData>>>{
+        self.construction.clone()
+    }
+
+    pub fn is_under_construction(&self)->bool{
+        self.construction.is_some()
+    }
+
+    pub fn set_construction(&mut self, id: &str)-> Result<(), GameError>{
+        self.construction = Some(self.resource_manager.get_resource(id)?.clone());
+        Ok(())
+    }
+
+    pub fn clear_construction(&mut self){
+        self.construction = None;
+    }
+
+    pub fn remove_field_content(&mut self){
+        self.field_content = None;
+        self.get_quest_completed_till_last_build = false;
+        self.active = true;
+        self.paid_costs.clear();
+        self.upgraded_from.clear();
+    }
+
+    /// Records `cost` paid for the content just built. `upgraded` is the
+    /// content it replaced as the next level of its path, any other build
+    /// starts the record over.
+    pub fn record_build(&mut self, cost: HashMap<GResource, u32>, upgraded: Option<Rc<Resource<FieldTypeData>>>){
+        match upgraded {
+            Some(old) => self.upgraded_from.push(old),
+            None => {
+                self.paid_costs.clear();
+                self.upgraded_from.clear();
+            }
+        }
+        self.paid_costs.push(cost);
+    }
+
+    /// Puts back the content the current one was upgraded from (nothing when
+    /// it was not an upgrade) and returns the cost paid for the current one,
+    /// empty when nothing was paid for it here.
+    pub fn downgrade(&mut self) -> HashMap<GResource, u32>{
+        self.field_content = self.upgraded_from.pop();
+        self.get_quest_completed_till_last_build = false;
+        self.active = true;
+        self.first_built = false;
+        self.paid_costs.pop().unwrap_or_default()
+    }
+
+    pub fn is_first_built(&self)->bool{
+        self.first_built
+    }
+
+    pub fn set_first_built(&mut self, first_built: bool){
+        self.first_built = first_built;
+    }
+
+    pub fn set_quest_completed(&mut self){
+        self.get_quest_completed_till_last_build = true;
+    }
+
+    /// `false` when the upkeep of the content was not paid last turn.
+    pub fn is_active(&self)->bool{
+        self.active
+    }
+
+    pub fn set_active(&mut self, active: bool){
+        self.active = active;
+    }
+
     pub fn set_visible(&mut self, visible: bool){
         self.visible = visible;
     }
 
+    #[cfg(feature = "color")]
     pub fn get_colored_string(&self) -> ColoredString{
+        let (r,g,b) = self.get_color();
+        self.get_short_name().truecolor(r,g,b)
+    }
+
+    /// Two letter label used when drawing the map, e.g. `v~` for a village
+    /// under construction.
+    pub fn get_short_name(&self) -> String{
+        if let Some(construction) = &self.construction {
+            return construction.id.chars().take(1).chain(['~']).collect();
+        }
         match &self.field_content {
-            Some(content) => {
-                let (r,g,b) = self.color_based_on_visibility(content.data.color);
-                content.id.chars().take(2).collect::<String>().truecolor(r,g,b)
-            },
-            None => {
-                let (r,g,b) = self.color_based_on_visibility(self.base_field_type.data.color);
-                self.base_field_type.id.chars().take(2).collect::<String>().truecolor(r,g,b)
-            },
+            Some(content) => content.id.chars().take(2).collect(),
+            None => self.base_field_type.id.chars().take(2).collect(),
+        }
+    }
+
+    /// Color of the content (or origin) dimmed when the tile is not visible.
+    pub fn get_color(&self) -> (u8,u8,u8){
+        match &self.field_content {
+            Some(content) => self.color_based_on_visibility(content.data.color),
+            None => self.color_based_on_visibility(self.base_field_type.data.color),
         }
     }
 
@@ -91,6 +196,11 @@ impl Debug for GameTile{
         .field("field_content", &self.field_content)
         .field("visible", &self.visible)
         .field("get_quest_completed_till_last_build", &self.get_quest_completed_till_last_build)
+        .field("active", &self.active)
+        .field("construction", &self.construction)
+        .field("paid_costs", &self.paid_costs)
+        .field("upgraded_from", &self.upgraded_from)
+        .field("first_built", &self.first_built)
         .finish()
     }
 }
\ No newline at end of file
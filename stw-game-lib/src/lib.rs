@@ -1,5 +1,14 @@
 pub mod resource;
 pub mod game;
+pub mod env;
+pub mod strategy;
+pub mod challenge;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "python")]
+mod python;
 pub use game::GameConfig;
 
 
@@ -0,0 +1,357 @@
+//! Python module `stw`, built with maturin (see `pyproject.toml`). Games are
+//! pickled through the save format - the replay is played back on load.
+
+use std::collections::BTreeMap;
+
+use pyo3::{prelude::*, create_exception, exceptions::PyException, types::PyTuple};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{game::{STWGame, GameError, map::TilePos, game_move::GameMove, game_controller::GameCallback, replay::Replay, state::{TileState, HeroState, QuestState}}, resource::{parse_resources, load_resources_from_dir, default_resources}, GameConfig};
+
+create_exception!(stw, StwError, PyException, "Game could not be created, loaded or saved.");
+create_exception!(stw, BadMoveError, PyException, "Move is not allowed in the current state.");
+
+fn game_error(e: GameError) -> PyErr{
+    StwError::new_err(e.msg)
+}
+
+/// Converts through json, so python gets the shapes from `docs/json-protocol.md`.
+fn to_py<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<Py<PyAny>>{
+    let text = serde_json::to_string(value).map_err(|e|StwError::new_err(e.to_string()))?;
+    Ok(py.import("json")?.call_method1("loads", (text,))?.unbind())
+}
+
+fn from_py<T: DeserializeOwned>(value: &Bound<'_, PyAny>) -> PyResult<T>{
+    let text: String = value.py().import("json")?.call_method1("dumps", (value,))?.extract()?;
+    serde_json::from_str(&text).map_err(|e|StwError::new_err(e.to_string()))
+}
+
+fn resources_to_json(resources: &[(String, serde_json::Value)]) -> String{
+    serde_json::Value::Array(resources.iter()
+        .map(|e|serde_json::json!({"type": e.0, "resource": e.1}))
+        .collect()
+    ).to_string()
+}
+
+#[pyclass(name = "GameMove", module = "stw", frozen, eq, skip_from_py_object)]
+#[derive(Clone, PartialEq)]
+pub struct PyGameMove(GameMove);
+
+#[pymethods]
+impl PyGameMove {
+    #[staticmethod]
+    fn build(q: i32, r: i32, id: &str) -> Self{
+        PyGameMove(GameMove::Build(TilePos { q, r }, id.to_string()))
+    }
+
+    #[staticmethod]
+    fn wait() -> Self{
+        PyGameMove(GameMove::Wait)
+    }
+
+    #[staticmethod]
+    fn start_history(q: i32, r: i32, hero: usize) -> Self{
+        PyGameMove(GameMove::StartHistory(TilePos { q, r }, hero))
+    }
+
+    #[staticmethod]
+    fn play_move(q: i32, r: i32, action: &str) -> Self{
+        PyGameMove(GameMove::PlayMove(TilePos { q, r }, action.to_string()))
+    }
+
+    #[staticmethod]
+    fn make_decision(decision: &str) -> Self{
+        PyGameMove(GameMove::MakeDecision(decision.to_string()))
+    }
+
+    #[staticmethod]
+    fn rename_hero(hero: usize, name: &str) -> Self{
+        PyGameMove(GameMove::RenameHero(hero, name.to_string()))
+    }
+
+    #[staticmethod]
+    fn cancel_construction(q: i32, r: i32) -> Self{
+        PyGameMove(GameMove::CancelConstruction(TilePos { q, r }))
+    }
+
+    #[staticmethod]
+    fn demolish(q: i32, r: i32) -> Self{
+        PyGameMove(GameMove::Demolish(TilePos { q, r }))
+    }
+
+    /// Resources by name, e.g. `GameMove.trade("Gold", "GreenTrophy", 1)`.
+    #[staticmethod]
+    fn trade(give: &str, take: &str, amount: u32) -> PyResult<Self>{
+        let resource = |name: &str|serde_json::from_value(serde_json::Value::String(name.to_string()))
+            .map_err(|e|StwError::new_err(format!("Unknown resource {}: {}", name, e)));
+        Ok(PyGameMove(GameMove::Trade { give: resource(give)?, take: resource(take)?, amount }))
+    }
+
+    /// Reads `{"type": ..., "data": ...}`, as produced by `to_dict`.
+    #[staticmethod]
+    fn from_dict(value: &Bound<'_, PyAny>) -> PyResult<Self>{
+        Ok(PyGameMove(from_py(value)?))
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>>{
+        to_py(py, &self.0)
+    }
+
+    fn __repr__(&self) -> String{
+        format!("GameMove.{:?}", self.0)
+    }
+}
+
+#[pyclass(name = "GameCallback", module = "stw", frozen, eq, skip_from_py_object)]
+#[derive(Clone, PartialEq)]
+pub struct PyGameCallback(GameCallback);
+
+#[pymethods]
+impl PyGameCallback {
+    /// Variant name, e.g. `"NewTileContent"`.
+    #[getter(r#type)]
+    fn callback_type(&self) -> String{
+        serde_json::to_value(&self.0).ok()
+            .and_then(|e|e["type"].as_str().map(|t|t.to_string()))
+            .unwrap_or_default()
+    }
+
+    #[getter]
+    fn data(&self, py: Python<'_>) -> PyResult<Py<PyAny>>{
+        let value = serde_json::to_value(&self.0).map_err(|e|StwError::new_err(e.to_string()))?;
+        to_py(py, &value["data"])
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>>{
+        to_py(py, &self.0)
+    }
+
+    fn __repr__(&self) -> String{
+        format!("GameCallback.{:?}", self.0)
+    }
+}
+
+#[pyclass(name = "Tile", module = "stw", frozen, get_all)]
+pub struct PyTile{
+    position: (i32, i32),
+    origin: String,
+    content: Option<String>,
+    visible: bool,
+    quest_completed: bool
+}
+
+impl From<TileState> for PyTile {
+    fn from(tile: TileState) -> Self {
+        PyTile {
+            position: (tile.position.q, tile.position.r),
+            origin: tile.origin,
+            content: tile.content,
+            visible: tile.visible,
+            quest_completed: tile.quest_completed
+        }
+    }
+}
+
+#[pyclass(name = "Hero", module = "stw", frozen, get_all)]
+pub struct PyHero{
+    id: String,
+    name: String,
+    family: String,
+    skills: BTreeMap<String, f32>
+}
+
+impl From<HeroState> for PyHero {
+    fn from(hero: HeroState) -> Self {
+        PyHero {
+            id: hero.id,
+            name: hero.name,
+            family: hero.family,
+            skills: hero.skills.iter().map(|e|(format!("{:?}", e.0), *e.1)).collect()
+        }
+    }
+}
+
+#[pyclass(name = "Quest", module = "stw", frozen, get_all)]
+pub struct PyQuest{
+    position: (i32, i32),
+    id: String,
+    creation_turn: u32
+}
+
+impl From<QuestState> for PyQuest {
+    fn from(quest: QuestState) -> Self {
+        PyQuest { position: (quest.position.q, quest.position.r), id: quest.id, creation_turn: quest.creation_turn }
+    }
+}
+
+#[pyclass(name = "GameConfig", module = "stw")]
+pub struct PyGameConfig{
+    resources: Vec<(String, serde_json::Value)>,
+    name: Option<String>,
+    seed: Option<String>,
+    map_size: Option<u32>
+}
+
+#[pymethods]
+impl PyGameConfig {
+    /// Config with the resources from `res/` compiled into the module.
+    #[new]
+    fn new() -> PyResult<Self>{
+        Ok(PyGameConfig { resources: default_resources().map_err(game_error)?, name: None, seed: None, map_size: None })
+    }
+
+    /// Json array of `{"type", "resource"}` entries.
+    fn set_resources(mut slf: PyRefMut<'_, Self>, json: String) -> PyResult<PyRefMut<'_, Self>>{
+        slf.resources = parse_resources(&json).map_err(game_error)?;
+        Ok(slf)
+    }
+
+    fn set_resources_dir(mut slf: PyRefMut<'_, Self>, path: String) -> PyResult<PyRefMut<'_, Self>>{
+        slf.resources = load_resources_from_dir(&path).map_err(game_error)?;
+        Ok(slf)
+    }
+
+    fn set_name(mut slf: PyRefMut<'_, Self>, name: String) -> PyRefMut<'_, Self>{
+        slf.name = Some(name);
+        slf
+    }
+
+    fn set_seed(mut slf: PyRefMut<'_, Self>, seed: String) -> PyRefMut<'_, Self>{
+        slf.seed = Some(seed);
+        slf
+    }
+
+    fn set_map_size(mut slf: PyRefMut<'_, Self>, map_size: u32) -> PyRefMut<'_, Self>{
+        slf.map_size = Some(map_size);
+        slf
+    }
+
+    fn start_game(&self) -> PyResult<PyGame>{
+        let mut config = GameConfig::new().set_resources(self.resources.clone());
+        if let Some(name) = &self.name {
+            config = config.set_name(name);
+        }
+        if let Some(seed) = &self.seed {
+            config = config.set_seed(seed);
+        }
+        if let Some(map_size) = self.map_size {
+            config = config.set_map_size(map_size);
+        }
+        let game = config.start_game().map_err(game_error)?;
+        Ok(PyGame { game, resources: self.resources.clone() })
+    }
+}
+
+#[pyclass(name = "STWGame", module = "stw", unsendable)]
+pub struct PyGame{
+    game: STWGame,
+    resources: Vec<(String, serde_json::Value)>
+}
+
+#[pymethods]
+impl PyGame {
+    /// Raises `BadMoveError` when the move is not allowed.
+    fn perform_move(&mut self, game_move: &PyGameMove) -> PyResult<Vec<PyGameCallback>>{
+        self.game.perform_move(&game_move.0)
+            .map(|e|e.into_iter().map(PyGameCallback).collect())
+            .map_err(|e|BadMoveError::new_err(e.msg))
+    }
+
+    /// Same as `perform_move`, for gym-like loops.
+    fn step(&mut self, game_move: &PyGameMove) -> PyResult<Vec<PyGameCallback>>{
+        self.perform_move(game_move)
+    }
+
+    fn legal_moves(&self) -> Vec<PyGameMove>{
+        self.game.get_possible_moves().into_iter().map(PyGameMove).collect()
+    }
+
+    /// `(id, not_enough_resources)` pairs.
+    fn possible_buildings(&self, q: i32, r: i32) -> Vec<(String, bool)>{
+        self.game.get_possible_building_at_position(&TilePos { q, r }).into_iter()
+            .map(|e|(e.id, e.not_enought_resources))
+            .collect()
+    }
+
+    fn possible_hero_moves(&self) -> Vec<((i32, i32), String)>{
+        self.game.get_possible_hero_moves().into_iter()
+            .map(|e|((e.0.q, e.0.r), e.1))
+            .collect()
+    }
+
+    fn possible_decisions(&self) -> Vec<String>{
+        self.game.get_possible_decisions()
+    }
+
+    #[getter]
+    fn turn(&self) -> u32{
+        self.game.get_turn()
+    }
+
+    #[getter]
+    fn resources(&self) -> BTreeMap<String, u32>{
+        self.game.get_resources().iter().map(|e|(format!("{:?}", e.0), *e.1)).collect()
+    }
+
+    /// `None` outside of the map.
+    fn tile(&self, q: i32, r: i32) -> Option<PyTile>{
+        self.game.get_tile_state(&TilePos { q, r }).map(PyTile::from)
+    }
+
+    fn tiles(&self) -> Vec<PyTile>{
+        self.game.get_state().tiles.into_iter().map(PyTile::from).collect()
+    }
+
+    fn heroes(&self) -> Vec<PyHero>{
+        self.game.get_state().heroes.into_iter().map(PyHero::from).collect()
+    }
+
+    fn quests(&self) -> Vec<PyQuest>{
+        self.game.get_state().quests.into_iter().map(PyQuest::from).collect()
+    }
+
+    /// Whole `GameState` as a dict.
+    fn state(&self, py: Python<'_>) -> PyResult<Py<PyAny>>{
+        to_py(py, &self.game.get_state())
+    }
+
+    /// Save file content (the replay json).
+    fn save(&self) -> PyResult<String>{
+        self.game.get_replay().to_json().map_err(game_error)
+    }
+
+    /// Plays back a save made with `save`, on the default resources unless
+    /// `resources` (json array) is given.
+    #[staticmethod]
+    #[pyo3(signature = (save, resources=None))]
+    fn load(save: &str, resources: Option<&str>) -> PyResult<PyGame>{
+        let resources = match resources {
+            Some(json) => parse_resources(json),
+            None => default_resources(),
+        }.map_err(game_error)?;
+        let game = Replay::from_json(save).map_err(game_error)?
+            .play(resources.clone())
+            .map_err(|e|StwError::new_err(e.to_string()))?;
+        Ok(PyGame { game, resources })
+    }
+
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyTuple>)>{
+        let this = slf.borrow();
+        let args = PyTuple::new(slf.py(), [this.save()?, resources_to_json(&this.resources)])?;
+        Ok((slf.get_type().getattr("load")?, args))
+    }
+}
+
+#[pymodule]
+fn stw(m: &Bound<'_, PyModule>) -> PyResult<()>{
+    m.add_class::<PyGameConfig>()?;
+    m.add_class::<PyGame>()?;
+    m.add_class::<PyGameMove>()?;
+    m.add_class::<PyGameCallback>()?;
+    m.add_class::<PyTile>()?;
+    m.add_class::<PyHero>()?;
+    m.add_class::<PyQuest>()?;
+    m.add("StwError", m.py().get_type::<StwError>())?;
+    m.add("BadMoveError", m.py().get_type::<BadMoveError>())?;
+    Ok(())
+}
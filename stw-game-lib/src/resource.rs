@@ -1,9 +1,11 @@
 pub mod resource_data;
 pub mod enums;
-use std::{rc::{Rc}, collections::{HashMap}, any::{Any, TypeId}};
+pub mod analysis;
+pub mod condition;
+use std::{rc::{Rc}, collections::{HashMap}, any::{Any, TypeId}, fs};
 use serde::{Deserialize, Serialize};
 use crate::game::GameError;
-use self::resource_data::{Globals, BaseResource};
+use self::resource_data::{Globals, BaseResource, OriginFieldData, FieldTypeData, HeroData, QuestData, ActionData, ExchangeData};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Resource<T>{
@@ -21,10 +23,33 @@ impl ResourceManager {
         ResourceManager { resources: HashMap::new() }
     }
 
+    /// Reads resources in the form produced by `parse_resources`. A later
+    /// resource replaces an earlier one with the same type and id.
+    pub fn from_resources(resources: Vec<(String, serde_json::Value)>) -> Result<ResourceManager, GameError>{
+        let mut rm = ResourceManager::new();
+        resources
+            .into_iter()
+            .try_for_each(|e| {
+                match e.0.as_str() {
+                    "Globals" => rm.add_resource::<Globals>(e.1),
+                    "OriginFieldData" => rm.add_resource::<OriginFieldData>(e.1),
+                    "FieldTypeData" => rm.add_resource::<FieldTypeData>(e.1),
+                    "HeroData" => rm.add_resource::<HeroData>(e.1),
+                    "QuestData" => rm.add_resource::<QuestData>(e.1),
+                    "ActionData" => rm.add_resource::<ActionData>(e.1),
+                    "ExchangeData" => rm.add_resource::<ExchangeData>(e.1),
+                    other => Err(GameError::new(format!("Unknown type of resource in ResourceManager.from_resources: {}", other)))
+                }
+            })?;
+        Ok(rm)
+    }
+
     pub(crate) fn add_resource<T: BaseResource + for<'a> Deserialize<'a> + 'static>(&mut self, json: serde_json::Value) -> Result<(), GameError>{
         let id = json["id"].as_str().ok_or_else(||GameError::new(format!("Resource {} has no id field", json)))?.to_string();
         match serde_json::from_value::<Resource<T>>(json) {
             Ok(resource) => {
+                resource.data.validate()
+                    .map_err(|e|GameError::new(format!("Invalid resource of type {} with id {} - {}", std::any::type_name::<T>(), id, e)))?;
                 let type_id = TypeId::of::<T>();
             self.resources.entry(type_id)
                 .or_insert_with(||HashMap::new())
@@ -65,13 +90,13 @@ impl ResourceManager {
         let type_id = TypeId::of::<T>();
         self.resources
             .get(&type_id)
-            .expect("Bad type in ResourceManager.get_resources")
-            .iter()
-            .map(|e|{(
-                e.0.as_str(),
-                e.1.clone().downcast::<Resource<T>>().unwrap()
-            )})
-            .collect()
+            .map(|r|r.iter()
+                .map(|e|{(
+                    e.0.as_str(),
+                    e.1.clone().downcast::<Resource<T>>().unwrap()
+                )})
+                .collect())
+            .unwrap_or_default()
     }
 
     pub(crate) fn _get_possible_names<T: BaseResource + 'static>(&self) -> Vec<&str>{
@@ -87,6 +112,82 @@ impl ResourceManager {
 }
 
 
+/// Reads a resource file - a json array of `{"type": ..., "resource": ...}`
+/// entries - into the form accepted by `GameConfig::set_resources`.
+pub fn parse_resources(text: &str) -> Result<Vec<(String, serde_json::Value)>, GameError>{
+    let json: serde_json::Value = serde_json::from_str(text)
+        .map_err(|e|GameError::new(format!("Unable to parse resources json: {}", e)))?;
+    json.as_array()
+        .ok_or_else(||GameError::new("Json is not an array of resources".to_string()))?
+        .iter()
+        .map(|e|{
+            let resource_type = e.get("type").and_then(|t|t.as_str())
+                .ok_or_else(||GameError::new(format!("Element {} has no type", e)))?;
+            let resource = e.get("resource")
+                .ok_or_else(||GameError::new(format!("Element {} has no resource", e)))?;
+            Ok((resource_type.to_string(), resource.clone()))
+        })
+        .collect()
+}
+
+/// Loads every `*.json` resource file from a directory (like `res/`).
+pub fn load_resources_from_dir(path: &str) -> Result<Vec<(String, serde_json::Value)>, GameError>{
+    let mut files = fs::read_dir(path)
+        .map_err(|e|GameError::new(format!("Can not read resource directory {}: {}", path, e)))?
+        .map(|e|e.map(|f|f.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e|GameError::new(format!("Can not read resource directory {}: {}", path, e)))?;
+    files.retain(|f|f.extension().is_some_and(|e|e == "json"));
+    files.sort();
+
+    let mut res = vec![];
+    for file in files {
+        let text = fs::read_to_string(&file)
+            .map_err(|e|GameError::new(format!("Unable to read file {:?}: {}", file, e)))?;
+        res.append(&mut parse_resources(&text)?);
+    }
+    Ok(res)
+}
+
+#[cfg(feature = "embedded-resources")]
+const DEFAULT_RESOURCES: [&str; 7] = [
+    include_str!("../res/actions.json"),
+    include_str!("../res/field_types.json"),
+    include_str!("../res/globals.json"),
+    include_str!("../res/heroes.json"),
+    include_str!("../res/market.json"),
+    include_str!("../res/origin_fields.json"),
+    include_str!("../res/quests.json"),
+];
+
+/// Resources from `res/` compiled into the library, for builds that can not
+/// read them from disk.
+#[cfg(feature = "embedded-resources")]
+pub fn default_resources() -> Result<Vec<(String, serde_json::Value)>, GameError>{
+    let mut res = vec![];
+    for text in DEFAULT_RESOURCES {
+        res.append(&mut parse_resources(text)?);
+    }
+    Ok(res)
+}
+
+/// Stable fingerprint of a resource set, independent of the order in which
+/// the resources were loaded. Used to check that a replay is played back
+/// against the same data it was recorded with.
+pub fn resources_hash(resources: &[(String, serde_json::Value)]) -> String{
+    let mut entries: Vec<String> = resources.iter()
+        .map(|e|format!("{}:{}", e.0, e.1))
+        .collect();
+    entries.sort();
+
+    // FNV-1a, std hashers are not guaranteed to be stable between releases
+    let hash = entries.iter()
+        .flat_map(|e|e.bytes().chain(std::iter::once(b'\n')))
+        .fold(0xcbf29ce484222325u64, |h, b|(h ^ b as u64).wrapping_mul(0x100000001b3));
+    format!("{:016x}", hash)
+}
+
+
 #[cfg(test)]
 mod tests {
 
@@ -0,0 +1,538 @@
+//! Static checks of a resource set, without playing a game. Starting from
+//! `map_middle` and `start_game_resources` it repeatedly adds every building
+//! whose cost, predecessor and requirements can be met by what is already
+//! reachable, together with the resources given by those buildings, by the
+//! quests they spawn and by the market exchanges they open. Whatever is left is reported as unreachable.
+//!
+//! The map is assumed to contain every origin and enough room, so `NoNear...`
+//! requirements only fail when they exclude every tile a matching `Near...`
+//! requirement could use. Only requirements that always have to hold are
+//! checked - `Any`, `Not` and game wide conditions are assumed to be met.
+
+use std::{collections::{BTreeMap, BTreeSet, HashMap}, rc::Rc};
+
+use serde::Serialize;
+use strum::IntoEnumIterator;
+
+use crate::game::GameError;
+use super::{Resource, ResourceManager, enums::*, resource_data::*};
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum GraphNode{
+    Building(String),
+    Resource(GResource),
+    Quest(String)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum EdgeKind{
+    /// Next `path_level` on the same `build_path`.
+    Upgrade,
+    Replaces,
+    Cost(u32),
+    Gives(u32),
+    /// Per turn, conditional production included.
+    Produces(u32),
+    Upkeep(u32),
+    SpawnsQuest,
+    /// Market exchange from the given resource.
+    Exchange,
+    Treasure,
+    Loses(u32)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct GraphEdge{
+    pub from: GraphNode,
+    pub to: GraphNode,
+    pub kind: EdgeKind
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BuildAnalysis{
+    pub reachable_buildings: BTreeSet<String>,
+    /// Why each building can not be built, as found in the last check.
+    pub unreachable_buildings: BTreeMap<String, Vec<String>>,
+    pub obtainable_resources: BTreeSet<GResource>,
+    /// Resources without a reachable source, with the buildings that cost them.
+    pub unobtainable_resources: BTreeMap<GResource, Vec<String>>,
+    pub spawnable_quests: BTreeSet<String>,
+    /// Build paths whose highest level can not be reached.
+    pub dead_end_paths: BTreeMap<String, String>,
+    /// Broken references in the data, like missing actions or quest families.
+    pub problems: Vec<String>,
+    pub edges: Vec<GraphEdge>
+}
+
+/// Analysis of a loaded resource set.
+pub fn analyze(rm: &ResourceManager) -> BuildAnalysis{
+    Analyzer::new(rm).run()
+}
+
+/// Same as `analyze`, but also reports resources sharing a type and id -
+/// `ResourceManager` keeps only the last of them.
+pub fn analyze_resources(resources: &[(String, serde_json::Value)]) -> Result<BuildAnalysis, GameError>{
+    let mut counts: BTreeMap<(&str, &str), u32> = BTreeMap::new();
+    for (resource_type, json) in resources {
+        *counts.entry((resource_type, json["id"].as_str().unwrap_or_default())).or_insert(0) += 1;
+    }
+    let mut res = analyze(&ResourceManager::from_resources(resources.to_vec())?);
+    let duplicates = counts.into_iter()
+        .filter(|e|e.1 > 1)
+        .map(|e|format!("Duplicate {} id {} ({} times) - only the last one is used", e.0.0, e.0.1, e.1));
+    res.problems.splice(0..0, duplicates);
+    Ok(res)
+}
+
+fn matches(building: &Resource<FieldTypeData>, requirment: &FieldRequirment) -> bool{
+    match requirment {
+        FieldRequirment::NearFieldWithCharacteristic { characteristic, .. } |
+        FieldRequirment::NoNearFieldWithCharacteristic { characteristic, .. } => building.data.characteristic == *characteristic,
+        FieldRequirment::NearFieldWithPath { build_path, .. } |
+        FieldRequirment::NoNearFieldWithPath { build_path, .. } => building.data.build_path == *build_path,
+        FieldRequirment::NearFieldWithId { id, .. } |
+        FieldRequirment::NoNearFieldWithId { id, .. } => building.id == *id,
+        _ => false,
+    }
+}
+
+fn weighted_levels(levels: &[f32]) -> impl Iterator<Item = u32> + '_{
+    levels.iter().enumerate().filter(|e|*e.1 > 0.0).map(|e|e.0 as u32)
+}
+
+/// Highest possible production of the building per turn.
+fn production_of(building: &Resource<FieldTypeData>) -> BTreeMap<GResource, u32>{
+    let mut res: BTreeMap<GResource, u32> = building.data.production.iter().map(|e|(*e.0, *e.1)).collect();
+    for production in building.data.conditional_production.iter() {
+        *res.entry(production.resource).or_insert(0) += production.amount;
+    }
+    res
+}
+
+fn sorted<T>(map: HashMap<&str, Rc<Resource<T>>>) -> BTreeMap<String, Rc<Resource<T>>>{
+    map.into_iter().map(|e|(e.0.to_string(), e.1)).collect()
+}
+
+struct Analyzer{
+    globals: Option<Rc<Resource<Globals>>>,
+    origins: BTreeSet<String>,
+    buildings: BTreeMap<String, Rc<Resource<FieldTypeData>>>,
+    quests: BTreeMap<String, Rc<Resource<QuestData>>>,
+    heroes: BTreeMap<String, Rc<Resource<HeroData>>>,
+    actions: BTreeMap<String, Rc<Resource<ActionData>>>,
+    exchanges: BTreeMap<String, Rc<Resource<ExchangeData>>>,
+    origin_actions: BTreeMap<String, Vec<String>>,
+
+    /// Reachable buildings with the origins they can stand on.
+    built: BTreeMap<String, BTreeSet<String>>,
+    resources: BTreeSet<GResource>
+}
+
+impl Analyzer {
+    fn new(rm: &ResourceManager) -> Analyzer{
+        let origin_data = rm.get_resources::<OriginFieldData>();
+        Analyzer {
+            globals: rm.get_resources::<Globals>().into_values().next(),
+            origins: origin_data.keys().map(|e|e.to_string()).collect(),
+            origin_actions: origin_data.iter().map(|e|(e.0.to_string(), e.1.data.possible_actions.clone())).collect(),
+            buildings: sorted(rm.get_resources::<FieldTypeData>()),
+            quests: sorted(rm.get_resources::<QuestData>()),
+            heroes: sorted(rm.get_resources::<HeroData>()),
+            actions: sorted(rm.get_resources::<ActionData>()),
+            exchanges: sorted(rm.get_resources::<ExchangeData>()),
+            built: BTreeMap::new(),
+            resources: BTreeSet::new()
+        }
+    }
+
+    fn run(mut self) -> BuildAnalysis{
+        let mut res = BuildAnalysis { problems: self.problems(), edges: self.edges(), ..Default::default() };
+
+        if let Some(globals) = &self.globals {
+            self.resources = globals.data.start_game_resources.iter().filter(|e|*e.1 > 0).map(|e|*e.0).collect();
+            if self.buildings.contains_key(&globals.data.map_middle) {
+                self.built.insert(globals.data.map_middle.to_string(), self.origins.clone());
+            }
+        }
+        loop {
+            let known = self.resources.len();
+            self.resources.extend(self.resource_sources());
+            let newly_built: Vec<_> = self.buildings.keys()
+                .filter(|e|!self.built.contains_key(*e))
+                .filter_map(|e|self.check_building(e).ok().map(|o|(e.to_string(), o)))
+                .collect();
+            if newly_built.is_empty() && self.resources.len() == known {
+                break;
+            }
+            self.built.extend(newly_built);
+        }
+
+        res.reachable_buildings = self.built.keys().cloned().collect();
+        res.unreachable_buildings = self.buildings.keys()
+            .filter(|e|!self.built.contains_key(*e))
+            .map(|e|(e.to_string(), self.check_building(e).err().unwrap_or_default()))
+            .collect();
+        res.obtainable_resources = self.resources.clone();
+        res.unobtainable_resources = GResource::iter()
+            .filter(|e|!self.resources.contains(e))
+            .map(|r|(r, self.buildings.values().filter(|b|b.data.cost.get(&r).is_some_and(|c|*c > 0)).map(|b|b.id.to_string()).collect()))
+            .collect();
+        res.spawnable_quests = self.spawnable_quests().into_iter().map(|e|e.id.to_string()).collect();
+        res.dead_end_paths = self.dead_end_paths();
+        res
+    }
+
+    fn quests_of(&self, building: &Resource<FieldTypeData>) -> Vec<&Rc<Resource<QuestData>>>{
+        let levels: BTreeSet<u32> = weighted_levels(&building.data.quest_levels).collect();
+        self.quests.values()
+            .filter(|q|q.data.quest_family == building.data.quest_family && levels.contains(&q.data.quest_level))
+            .collect()
+    }
+
+    fn spawnable_quests(&self) -> Vec<&Rc<Resource<QuestData>>>{
+        let mut res: Vec<_> = self.built.keys().flat_map(|e|self.quests_of(&self.buildings[e])).collect();
+        res.sort_by(|a, b|a.id.cmp(&b.id));
+        res.dedup_by(|a, b|a.id == b.id);
+        res
+    }
+
+    fn hero_families(&self) -> BTreeSet<&str>{
+        self.built.keys()
+            .map(|e|&self.buildings[e])
+            .filter(|b|weighted_levels(&b.data.hero_levels)
+                .any(|l|self.heroes.values().any(|h|h.data.hero_family == b.data.hero_family && h.data.hero_level == l)))
+            .map(|b|b.data.hero_family.as_str())
+            .collect()
+    }
+
+    fn available_actions(&self) -> BTreeSet<&str>{
+        self.origin_actions.values()
+            .flatten()
+            .chain(self.built.keys().flat_map(|e|self.buildings[e].data.possible_actions.iter()))
+            .filter(|e|self.actions.contains_key(*e))
+            .map(|e|e.as_str())
+            .collect()
+    }
+
+    fn is_decision_possible(&self, decision: &QuestDecision) -> bool{
+        let actions = self.available_actions();
+        let families = self.hero_families();
+        decision.required.is_empty() || decision.required.iter().any(|group|group.iter().flat_map(|e|e.required()).all(|req|match req {
+            PointRequirment::DidAction(id) => actions.contains(id.as_str()),
+            PointRequirment::DidActionFamily(family) => actions.iter().any(|a|self.actions[*a].data.action_family == *family),
+            PointRequirment::HeroFromFamliy(family) => families.contains(family.as_str()),
+        }))
+    }
+
+    /// Resources given or produced by reachable buildings, by possible
+    /// decisions of spawnable quests and by open exchanges of obtainable
+    /// resources. Treasure is truncated, so it has to be able to exceed 1.
+    fn resource_sources(&self) -> BTreeSet<GResource>{
+        let from_buildings = self.built.keys()
+            .flat_map(|e|self.buildings[e].data.instant_effects.iter())
+            .filter_map(|e|match e {
+                FieldInstantEffect::GiveResources { resource, amount, .. } if *amount > 0 => Some(*resource),
+                _ => None,
+            });
+        let produced = self.built.keys()
+            .flat_map(|e|production_of(&self.buildings[e]))
+            .filter(|e|e.1 > 0)
+            .map(|e|e.0);
+        let from_quests = self.spawnable_quests().into_iter()
+            .flat_map(|q|q.data.quest_decisions.iter())
+            .filter(|d|self.is_decision_possible(d))
+            .flat_map(|d|d.treasure.iter().filter(|t|t.1.1 > 1.0).map(|t|*t.0));
+        let exchanged = self.exchanges.values()
+            .filter(|e|self.resources.contains(&e.data.give))
+            .filter(|e|e.data.required_paths.is_empty()
+                || self.built.keys().any(|b|e.data.required_paths.contains(&self.buildings[b].data.build_path)))
+            .map(|e|e.data.take);
+        from_buildings.chain(produced).chain(from_quests).chain(exchanged).collect()
+    }
+
+    /// Origins the building can stand on, or why it can not be built.
+    fn check_building(&self, id: &str) -> Result<BTreeSet<String>, Vec<String>>{
+        let building = &self.buildings[id];
+        let requirments: Vec<&FieldRequirment> = building.data.requirments.iter().flat_map(|e|e.required()).collect();
+        let mut reasons = vec![];
+
+        if requirments.iter().any(|e|matches!(e, FieldRequirment::CantBuild)) {
+            reasons.push("has CantBuild requirment".to_string());
+        }
+        let mut cost: Vec<_> = building.data.cost.iter().filter(|e|*e.1 > 0 && !self.resources.contains(e.0)).collect();
+        cost.sort();
+        for (resource, amount) in cost {
+            reasons.push(format!("costs {} {:?} which can not be obtained", amount, resource));
+        }
+
+        let mut allowed = self.origins.clone();
+        for requirment in requirments.iter() {
+            match requirment {
+                FieldRequirment::HasOrigin(origin) => allowed.retain(|e|e == origin),
+                FieldRequirment::HasOriginOneOf(origins) => allowed.retain(|e|origins.contains(e)),
+                _ => {}
+            }
+        }
+        if allowed.is_empty() {
+            reasons.push("no origin meets the origin requirments".to_string());
+        }
+
+        // (origins, quest possible on the old tile) for every tile the building can replace
+        let needs_quest = requirments.iter().any(|e|matches!(e, FieldRequirment::QuestCompleted));
+        let mut options: Vec<(String, BTreeSet<String>, bool)> = vec![];
+        if building.data.path_level == 0 {
+            options.push(("an empty tile".to_string(), self.origins.clone(), false));
+        }
+        for (old_id, origins) in self.built.iter() {
+            let old = &self.buildings[old_id];
+            let next_in_path = old.data.build_path == building.data.build_path && old.data.path_level + 1 == building.data.path_level;
+            if next_in_path || requirments.iter().any(|e|e.allows_replacing(old)) {
+                options.push((old_id.to_string(), origins.clone(), !self.quests_of(old).is_empty()));
+            }
+        }
+        if options.is_empty() {
+            reasons.push(format!("no reachable {} level {} building to upgrade and nothing to replace", building.data.build_path, building.data.path_level as i32 - 1));
+        }
+        let mut origins = BTreeSet::new();
+        for (old, old_origins, has_quests) in options.iter() {
+            let common: BTreeSet<_> = old_origins.intersection(&allowed).cloned().collect();
+            if common.is_empty() && !allowed.is_empty() {
+                reasons.push(format!("{} can not stand on any of the allowed origins {:?}", old, allowed));
+            } else if needs_quest && !has_quests {
+                reasons.push(format!("needs QuestCompleted but {} never has a quest", old));
+            } else {
+                origins.extend(common);
+            }
+        }
+
+        for requirment in requirments.iter() {
+            let distance = match requirment {
+                FieldRequirment::NearFieldWithCharacteristic { distance, .. } |
+                FieldRequirment::NearFieldWithPath { distance, .. } |
+                FieldRequirment::NearFieldWithId { distance, .. } => *distance,
+                _ => continue,
+            };
+            let excluded = |b: &Resource<FieldTypeData>|requirments.iter().any(|other|match other {
+                FieldRequirment::NoNearFieldWithCharacteristic { distance: d, .. } |
+                FieldRequirment::NoNearFieldWithPath { distance: d, .. } |
+                FieldRequirment::NoNearFieldWithId { distance: d, .. } => *d >= distance && matches(b, other),
+                _ => false,
+            });
+            if distance == 0 {
+                reasons.push(format!("{:?} never matches - distance has to be at least 1", requirment));
+            } else if !self.built.keys().map(|e|&self.buildings[e]).any(|b|matches(b, requirment) && !excluded(b)) {
+                reasons.push(format!("{:?} has no reachable building to match", requirment));
+            }
+        }
+
+        match reasons.is_empty() && !origins.is_empty() {
+            true => Ok(origins),
+            false => Err(reasons),
+        }
+    }
+
+    fn dead_end_paths(&self) -> BTreeMap<String, String>{
+        let mut levels: BTreeMap<&str, (u32, Option<u32>)> = BTreeMap::new();
+        for building in self.buildings.values() {
+            let entry = levels.entry(building.data.build_path.as_str()).or_insert((0, None));
+            entry.0 = entry.0.max(building.data.path_level);
+            if self.built.contains_key(&building.id) {
+                entry.1 = entry.1.max(Some(building.data.path_level));
+            }
+        }
+        levels.into_iter()
+            .filter(|e|e.1.1 != Some(e.1.0))
+            .map(|(path, (max, reached))|(path.to_string(), match reached {
+                Some(level) => format!("stops at level {} of {}", level, max),
+                None => format!("no level of {} is reachable", max + 1),
+            }))
+            .collect()
+    }
+
+    fn problems(&self) -> Vec<String>{
+        let mut res = vec![];
+        let building_ids: BTreeSet<&str> = self.buildings.keys().map(|e|e.as_str()).collect();
+        let paths: BTreeSet<&str> = self.buildings.values().map(|e|e.data.build_path.as_str()).collect();
+
+        match &self.globals {
+            None => res.push("No Globals resource".to_string()),
+            Some(globals) => {
+                if !building_ids.contains(globals.data.map_middle.as_str()) {
+                    res.push(format!("map_middle {} is not a FieldTypeData", globals.data.map_middle));
+                }
+                if !paths.contains(globals.data.win_cond_build_path.as_str()) {
+                    res.push(format!("win_cond_build_path {} has no buildings", globals.data.win_cond_build_path));
+                }
+                for origin in globals.data.map_near_mid_enable.iter().filter(|e|!self.origins.contains(*e)) {
+                    res.push(format!("map_near_mid_enable has unknown origin {}", origin));
+                }
+                for condition in globals.data.objectives.win.iter() {
+                    if let WinCondition::PathLevel { build_path, level } = condition {
+                        if !self.buildings.values().any(|e|e.data.build_path == *build_path && e.data.path_level >= *level) {
+                            res.push(format!("win condition needs level {} of {} which has no building", level, build_path));
+                        }
+                    }
+                }
+            }
+        }
+
+        for (origin, actions) in self.origin_actions.iter() {
+            for action in actions.iter().filter(|e|!self.actions.contains_key(*e)) {
+                res.push(format!("Origin {} offers missing action {}", origin, action));
+            }
+        }
+        for building in self.buildings.values() {
+            for action in building.data.possible_actions.iter().filter(|e|!self.actions.contains_key(*e)) {
+                res.push(format!("Building {} offers missing action {}", building.id, action));
+            }
+            for level in weighted_levels(&building.data.quest_levels) {
+                if !self.quests.values().any(|q|q.data.quest_family == building.data.quest_family && q.data.quest_level == level) {
+                    res.push(format!("Building {} spawns quests of family {} level {} but there are none", building.id, building.data.quest_family, level));
+                }
+            }
+            for level in weighted_levels(&building.data.hero_levels) {
+                if !self.heroes.values().any(|h|h.data.hero_family == building.data.hero_family && h.data.hero_level == level) {
+                    res.push(format!("Building {} spawns heroes of family {} level {} but there are none", building.id, building.data.hero_family, level));
+                }
+            }
+            for requirment in building.data.requirments.iter().flat_map(|e|e.requirments()) {
+                let known = match requirment {
+                    FieldRequirment::Replaces(id) |
+                    FieldRequirment::NearFieldWithId { id, .. } |
+                    FieldRequirment::NoNearFieldWithId { id, .. } => building_ids.contains(id.as_str()),
+                    FieldRequirment::ReplacesPath(build_path) |
+                    FieldRequirment::NearFieldWithPath { build_path, .. } |
+                    FieldRequirment::NoNearFieldWithPath { build_path, .. } => paths.contains(build_path.as_str()),
+                    FieldRequirment::HasOrigin(origin) => self.origins.contains(origin),
+                    FieldRequirment::HasOriginOneOf(origins) => origins.iter().all(|e|self.origins.contains(e)),
+                    _ => true,
+                };
+                if !known {
+                    res.push(format!("Building {} has requirment {:?} with an unknown reference", building.id, requirment));
+                }
+            }
+        }
+
+        let hero_families: BTreeSet<&str> = self.heroes.values().map(|e|e.data.hero_family.as_str()).collect();
+        let action_families: BTreeSet<&str> = self.actions.values().map(|e|e.data.action_family.as_str()).collect();
+        for quest in self.quests.values() {
+            if !self.buildings.values().any(|b|self.quests_of(b).iter().any(|q|q.id == quest.id)) {
+                res.push(format!("Quest {} (family {} level {}) is never spawned by any building", quest.id, quest.data.quest_family, quest.data.quest_level));
+            }
+            for decision in quest.data.quest_decisions.iter() {
+                for requirment in decision.additional_points.iter().chain(decision.required.iter().flatten().flat_map(|e|e.requirments())) {
+                    let known = match requirment {
+                        PointRequirment::DidAction(id) => self.actions.contains_key(id),
+                        PointRequirment::DidActionFamily(family) => action_families.contains(family.as_str()),
+                        PointRequirment::HeroFromFamliy(family) => hero_families.contains(family.as_str()),
+                    };
+                    if !known {
+                        res.push(format!("Decision {} of quest {} uses {:?} which never happens", decision.id, quest.id, requirment));
+                    }
+                }
+            }
+        }
+        res.dedup();
+        res
+    }
+
+    fn edges(&self) -> Vec<GraphEdge>{
+        let mut res = vec![];
+        let mut edge = |from, to, kind|res.push(GraphEdge { from, to, kind });
+        for building in self.buildings.values() {
+            let node = GraphNode::Building(building.id.to_string());
+            for old in self.buildings.values() {
+                if old.data.build_path == building.data.build_path && old.data.path_level + 1 == building.data.path_level {
+                    edge(GraphNode::Building(old.id.to_string()), node.clone(), EdgeKind::Upgrade);
+                }
+            }
+            for old in self.buildings.values() {
+                if building.replaces(old) {
+                    edge(GraphNode::Building(old.id.to_string()), node.clone(), EdgeKind::Replaces);
+                }
+            }
+            for (resource, amount) in building.data.cost.iter() {
+                edge(GraphNode::Resource(*resource), node.clone(), EdgeKind::Cost(*amount));
+            }
+            for effect in building.data.instant_effects.iter() {
+                if let FieldInstantEffect::GiveResources { resource, amount, .. } = effect {
+                    edge(node.clone(), GraphNode::Resource(*resource), EdgeKind::Gives(*amount));
+                }
+            }
+            for (resource, amount) in production_of(building) {
+                edge(node.clone(), GraphNode::Resource(resource), EdgeKind::Produces(amount));
+            }
+            for (resource, amount) in building.data.upkeep.iter() {
+                edge(GraphNode::Resource(*resource), node.clone(), EdgeKind::Upkeep(*amount));
+            }
+            for quest in self.quests_of(building) {
+                edge(node.clone(), GraphNode::Quest(quest.id.to_string()), EdgeKind::SpawnsQuest);
+            }
+        }
+        for exchange in self.exchanges.values() {
+            edge(GraphNode::Resource(exchange.data.give), GraphNode::Resource(exchange.data.take), EdgeKind::Exchange);
+        }
+        for quest in self.quests.values() {
+            let node = GraphNode::Quest(quest.id.to_string());
+            for resource in quest.data.quest_decisions.iter().flat_map(|d|d.treasure.iter().filter(|t|t.1.1 > 0.0).map(|t|*t.0)) {
+                edge(node.clone(), GraphNode::Resource(resource), EdgeKind::Treasure);
+            }
+            for penalty in quest.data.penalty.iter() {
+                if let QuestPenalty::LooseResources { resource, amount } = penalty {
+                    edge(node.clone(), GraphNode::Resource(*resource), EdgeKind::Loses(*amount));
+                }
+            }
+        }
+        res.sort();
+        res.dedup();
+        res
+    }
+}
+
+fn dot_id(node: &GraphNode) -> String{
+    match node {
+        GraphNode::Building(id) => format!("\"building:{}\"", id),
+        GraphNode::Resource(resource) => format!("\"resource:{:?}\"", resource),
+        GraphNode::Quest(id) => format!("\"quest:{}\"", id),
+    }
+}
+
+impl BuildAnalysis {
+    /// Graphviz DOT of the build and resource graph. Unreachable buildings,
+    /// unobtainable resources and quests that never spawn are drawn red.
+    pub fn to_dot(&self) -> String{
+        let mut nodes: BTreeSet<&GraphNode> = self.edges.iter().flat_map(|e|[&e.from, &e.to]).collect();
+        let buildings: Vec<GraphNode> = self.reachable_buildings.iter().chain(self.unreachable_buildings.keys())
+            .map(|e|GraphNode::Building(e.to_string()))
+            .collect();
+        nodes.extend(buildings.iter());
+
+        let mut res = vec!["digraph stw {".to_string(), "    rankdir=LR;".to_string()];
+        for node in nodes {
+            let (label, shape, ok) = match node {
+                GraphNode::Building(id) => (id.to_string(), "box", self.reachable_buildings.contains(id)),
+                GraphNode::Resource(resource) => (format!("{:?}", resource), "ellipse", self.obtainable_resources.contains(resource)),
+                GraphNode::Quest(id) => (id.to_string(), "diamond", self.spawnable_quests.contains(id)),
+            };
+            let style = if ok { "" } else { ", color=red, style=dashed" };
+            res.push(format!("    {} [label=\"{}\", shape={}{}];", dot_id(node), label, shape, style));
+        }
+        for edge in self.edges.iter() {
+            let attributes = match edge.kind {
+                EdgeKind::Upgrade => "label=\"upgrade\"".to_string(),
+                EdgeKind::Replaces => "label=\"replaces\", style=dashed".to_string(),
+                EdgeKind::Cost(amount) => format!("label=\"cost {}\"", amount),
+                EdgeKind::Gives(amount) => format!("label=\"gives {}\", color=darkgreen", amount),
+                EdgeKind::Produces(amount) => format!("label=\"produces {}/turn\", color=darkgreen", amount),
+                EdgeKind::Upkeep(amount) => format!("label=\"upkeep {}/turn\"", amount),
+                EdgeKind::SpawnsQuest => "label=\"quest\", style=dotted".to_string(),
+                EdgeKind::Exchange => "label=\"exchange\", color=darkgreen, style=dashed".to_string(),
+                EdgeKind::Treasure => "label=\"treasure\", color=darkgreen".to_string(),
+                EdgeKind::Loses(amount) => format!("label=\"loses {}\", color=red", amount),
+            };
+            res.push(format!("    {} -> {} [{}];", dot_id(&edge.from), dot_id(&edge.to), attributes));
+        }
+        res.push("}".to_string());
+        res.join("\n") + "\n"
+    }
+}
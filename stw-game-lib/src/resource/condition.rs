@@ -0,0 +1,150 @@
+//! Requirement expressions shared by buildings, actions and quest decisions.
+//! A condition combines the requirements of its owner (`T`) with `All`,
+//! `Any` and `Not` and with leaves that look at the whole game. Plain
+//! requirements parse as conditions too, so the old lists in resources stay
+//! valid - a list of conditions is met when all of them are.
+//!
+//! ```json
+//! {"Any": [{"HasOrigin": "forest"}, {"HasOrigin": "hills"}]}
+//! {"Not": {"HasResource": {"resource": "Gold", "amount": 10}}}
+//! ```
+
+use std::{collections::HashMap, fmt::{Debug, Display}};
+
+use serde::{Deserialize, Serialize};
+
+use super::enums::GResource;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum Condition<T>{
+    All(Vec<Condition<T>>),
+    /// Never met when empty.
+    Any(Vec<Condition<T>>),
+    Not(Box<Condition<T>>),
+    /// At least `amount` owned.
+    HasResource{resource: GResource, amount: u32},
+    TurnAtLeast(u32),
+    /// At least this many heroes alive.
+    HeroCount(u32),
+    /// At least `amount` buildings built in the game, only `id` ones when set.
+    BuildingsBuilt{#[serde(default)] id: Option<String>, amount: u32},
+    #[serde(untagged)]
+    Requirment(T)
+}
+
+/// Game state seen by the game wide leaves of a `Condition`.
+pub struct ConditionFacts<'a>{
+    pub resources: &'a HashMap<GResource, u32>,
+    pub turn: u32,
+    pub heroes: usize,
+    /// Id of every building built so far, in order.
+    pub built: &'a [String]
+}
+
+impl<T: Debug + Display> Condition<T> {
+    /// `Err` says why the condition is not met. `leaf` checks the
+    /// requirements of the owner in the same way.
+    pub fn check(&self, facts: &ConditionFacts, leaf: &impl Fn(&T) -> Result<(), String>) -> Result<(), String>{
+        match self {
+            Condition::All(conditions) => {
+                let failed: Vec<String> = conditions.iter().filter_map(|e|e.check(facts, leaf).err()).collect();
+                match failed.is_empty() {
+                    true => Ok(()),
+                    false => Err(failed.join("; ")),
+                }
+            },
+            Condition::Any(conditions) => {
+                let mut failed = vec![];
+                for condition in conditions {
+                    match condition.check(facts, leaf) {
+                        Ok(()) => return Ok(()),
+                        Err(e) => failed.push(e),
+                    }
+                }
+                Err(format!("none of: {}", failed.join(" | ")))
+            },
+            Condition::Not(condition) => match condition.check(facts, leaf) {
+                Ok(()) => Err(format!("{} is met", condition.describe())),
+                Err(_) => Ok(()),
+            },
+            Condition::HasResource { resource, amount } => {
+                let owned = *facts.resources.get(resource).unwrap_or(&0);
+                match owned >= *amount {
+                    true => Ok(()),
+                    false => Err(format!("{} {:?} needed, {} owned", amount, resource, owned)),
+                }
+            },
+            Condition::TurnAtLeast(turn) => match facts.turn >= *turn {
+                true => Ok(()),
+                false => Err(format!("turn {} not reached, now {}", turn, facts.turn)),
+            },
+            Condition::HeroCount(count) => match facts.heroes >= *count as usize {
+                true => Ok(()),
+                false => Err(format!("{} heroes needed, {} alive", count, facts.heroes)),
+            },
+            Condition::BuildingsBuilt { id, amount } => {
+                let built = facts.built.iter().filter(|e|id.as_ref().is_none_or(|i|i == *e)).count();
+                match built >= *amount as usize {
+                    true => Ok(()),
+                    false => Err(format!("{} {} built, {} needed", built, id.as_deref().unwrap_or("buildings"), amount)),
+                }
+            },
+            Condition::Requirment(requirment) => leaf(requirment),
+        }
+    }
+
+    pub fn is_met(&self, facts: &ConditionFacts, leaf: &impl Fn(&T) -> Result<(), String>) -> bool{
+        self.check(facts, leaf).is_ok()
+    }
+
+    /// Short text of the condition, e.g. `any of (HasOrigin forest, HasOrigin hills)`.
+    pub fn describe(&self) -> String{
+        let join = |conditions: &Vec<Condition<T>>|conditions.iter().map(|e|e.describe()).collect::<Vec<_>>().join(", ");
+        match self {
+            Condition::All(conditions) => format!("all of ({})", join(conditions)),
+            Condition::Any(conditions) => format!("any of ({})", join(conditions)),
+            Condition::Not(condition) => format!("not {}", condition.describe()),
+            Condition::Requirment(requirment) => requirment.to_string(),
+            other => format!("{:?}", other),
+        }
+    }
+}
+
+impl<T> Condition<T> {
+    /// The plain requirement, when this is one.
+    pub fn as_requirment(&self) -> Option<&T>{
+        match self {
+            Condition::Requirment(requirment) => Some(requirment),
+            _ => None,
+        }
+    }
+
+    /// Requirements that have to be met for the condition to be met - the
+    /// ones reached only through `All`.
+    pub fn required(&self) -> Vec<&T>{
+        match self {
+            Condition::All(conditions) => conditions.iter().flat_map(|e|e.required()).collect(),
+            Condition::Requirment(requirment) => vec![requirment],
+            _ => vec![],
+        }
+    }
+
+    /// Every requirement in the condition.
+    pub fn requirments(&self) -> Vec<&T>{
+        match self {
+            Condition::All(conditions) | Condition::Any(conditions) => conditions.iter().flat_map(|e|e.requirments()).collect(),
+            Condition::Not(condition) => condition.requirments(),
+            Condition::Requirment(requirment) => vec![requirment],
+            _ => vec![],
+        }
+    }
+}
+
+/// `Condition::check` of every condition of a list, failures joined.
+pub fn check_all<T: Debug + Display>(conditions: &[Condition<T>], facts: &ConditionFacts, leaf: &impl Fn(&T) -> Result<(), String>) -> Result<(), String>{
+    let failed: Vec<String> = conditions.iter().filter_map(|e|e.check(facts, leaf).err()).collect();
+    match failed.is_empty() {
+        true => Ok(()),
+        false => Err(failed.join("; ")),
+    }
+}
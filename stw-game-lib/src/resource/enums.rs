@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 
@@ -17,7 +19,7 @@ pub enum FieldInstantEffect{
     IncreaseMaxHeroes{only_first_time: bool}
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)] 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumIter)] 
 pub enum GResource {
     Story,
     Gold,
@@ -30,7 +32,7 @@ pub enum GResource {
     LegendTrophy
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)] 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumIter)] 
 pub enum HeroSkill {
     Intnteligence,
     Alechemy,
@@ -56,7 +58,13 @@ pub enum PointRequirment {
 #[derive(Serialize, Deserialize, Debug)]
 pub enum FieldRequirment {
     CantBuild,
+    /// Building can not be removed with `GameMove::Demolish`.
+    CantDemolish,
+    /// Building can be put on the given content, besides the previous
+    /// level of its own path. See `FieldTypeData::replacement`.
     Replaces(String),
+    ReplacesPath(String),
+    ReplacesCharacteristic(FieldCharacteristic),
     QuestCompleted,
 
     NearFieldWithCharacteristic{ characteristic: FieldCharacteristic, distance: u32 },
@@ -69,6 +77,28 @@ pub enum FieldRequirment {
     HasOriginOneOf(Vec<String>)
 }
 
+/// Short form used in explanations, e.g. `NoNearFieldWithPath town within 5`.
+impl Display for FieldRequirment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldRequirment::CantBuild => write!(f, "CantBuild"),
+            FieldRequirment::CantDemolish => write!(f, "CantDemolish"),
+            FieldRequirment::Replaces(id) => write!(f, "Replaces {}", id),
+            FieldRequirment::ReplacesPath(build_path) => write!(f, "ReplacesPath {}", build_path),
+            FieldRequirment::ReplacesCharacteristic(characteristic) => write!(f, "ReplacesCharacteristic {:?}", characteristic),
+            FieldRequirment::QuestCompleted => write!(f, "QuestCompleted"),
+            FieldRequirment::NearFieldWithCharacteristic { characteristic, distance } => write!(f, "NearFieldWithCharacteristic {:?} within {}", characteristic, distance),
+            FieldRequirment::NearFieldWithPath { build_path, distance } => write!(f, "NearFieldWithPath {} within {}", build_path, distance),
+            FieldRequirment::NearFieldWithId { id, distance } => write!(f, "NearFieldWithId {} within {}", id, distance),
+            FieldRequirment::NoNearFieldWithCharacteristic { characteristic, distance } => write!(f, "NoNearFieldWithCharacteristic {:?} within {}", characteristic, distance),
+            FieldRequirment::NoNearFieldWithPath { build_path, distance } => write!(f, "NoNearFieldWithPath {} within {}", build_path, distance),
+            FieldRequirment::NoNearFieldWithId { id, distance } => write!(f, "NoNearFieldWithId {} within {}", id, distance),
+            FieldRequirment::HasOrigin(id) => write!(f, "HasOrigin {}", id),
+            FieldRequirment::HasOriginOneOf(ids) => write!(f, "HasOriginOneOf {}", ids.join(", ")),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub enum QuestPenalty {
     LooseResources{resource: GResource, amount: u32},
@@ -85,3 +115,42 @@ pub enum ActionRequirment {
     IsBeforePosition(usize),
 }
 
+impl Display for ActionRequirment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActionRequirment::IsBefore(id) => write!(f, "IsBefore {}", id),
+            ActionRequirment::IsBeforeFamily(family) => write!(f, "IsBeforeFamily {}", family),
+            ActionRequirment::IsBeforePosition(position) => write!(f, "IsBeforePosition {}", position),
+        }
+    }
+}
+
+impl Display for PointRequirment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PointRequirment::DidAction(id) => write!(f, "DidAction {}", id),
+            PointRequirment::DidActionFamily(family) => write!(f, "DidActionFamily {}", family),
+            PointRequirment::HeroFromFamliy(family) => write!(f, "HeroFromFamliy {}", family),
+        }
+    }
+}
+
+
+/// Game is won as soon as one of these is met.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum WinCondition {
+    PathLevel{build_path: String, level: u32},
+    Collect{resource: GResource, amount: u32},
+    QuestsCompleted(u32),
+    SurviveTurns(u32)
+}
+
+/// Game is lost as soon as one of these is met.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum LossCondition {
+    /// Some hero was born and all heroes died since.
+    AllHeroesDead,
+    MiddleDestroyed,
+    /// The game turn went past this value.
+    TurnLimit(u32)
+}
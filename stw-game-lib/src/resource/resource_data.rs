@@ -4,16 +4,41 @@ use std::rc::Rc;
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::game::map::TilePos;
+
 use super::Resource;
+use super::condition::{Condition, ConditionFacts, check_all};
 use super::enums::*;
 
-pub trait BaseResource{}
+pub trait BaseResource{
+    /// Checks what the json format can not, `Err` says what is wrong.
+    fn validate(&self) -> Result<(), String>{
+        Ok(())
+    }
+}
 impl BaseResource for OriginFieldData {}
-impl BaseResource for FieldTypeData {}
+impl BaseResource for FieldTypeData {
+    /// `Replaces` requirments are checked against the old content before
+    /// the conditions, so they can only be required - not inside `Any` or
+    /// `Not`, nor in `conditional_production`.
+    fn validate(&self) -> Result<(), String>{
+        let replacements = |requirments: Vec<&FieldRequirment>|requirments.into_iter().filter(|e|e.is_replacement()).count();
+        let required = replacements(self.requirments.iter().flat_map(|e|e.required()).collect());
+        let all = replacements(self.requirments.iter().flat_map(|e|e.requirments()).collect());
+        if required != all {
+            return Err("Replaces, ReplacesPath and ReplacesCharacteristic can not be used inside Any or Not".to_string());
+        }
+        if self.conditional_production.iter().flat_map(|e|e.requirments.iter()).flat_map(|e|e.requirments()).any(|e|e.is_replacement()) {
+            return Err("Replaces, ReplacesPath and ReplacesCharacteristic can not be used in conditional_production".to_string());
+        }
+        Ok(())
+    }
+}
 impl BaseResource for Globals {}
 impl BaseResource for QuestData {}
 impl BaseResource for HeroData {}
 impl BaseResource for ActionData {}
+impl BaseResource for ExchangeData {}
 
 //
 
@@ -28,7 +53,45 @@ pub struct Globals{
     pub map_near_mid_enable: Vec<String>,
     pub map_visible_on_start: u32,
 
-    pub win_cond_build_path: String
+    pub win_cond_build_path: String,
+    #[serde(default)]
+    pub objectives: Objectives,
+    #[serde(default)]
+    pub scoring: Scoring,
+    /// Share of the cost returned when a construction is cancelled.
+    #[serde(default)]
+    pub construction_refund: f32,
+    /// Share of the cost returned when a building is demolished.
+    #[serde(default)]
+    pub demolish_refund: f32
+}
+
+/// End of the game. Without conditions the game never ends.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Objectives{
+    pub win: Vec<WinCondition>,
+    pub loss: Vec<LossCondition>
+}
+
+/// Weights of the game score, missing ones are 0. Negative weights are
+/// penalties, the score itself never goes below 0.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Scoring{
+    /// For every unit owned at the end.
+    pub resources: HashMap<GResource, f32>,
+    /// For every unit earned during the game.
+    pub resources_earned: HashMap<GResource, f32>,
+    pub building: f32,
+    pub quest_completed: f32,
+    pub quest_failed: f32,
+    pub hero_recruited: f32,
+    pub hero_lost: f32,
+    /// For every skill point of the heroes alive at the end.
+    pub skill: f32,
+    pub turn: f32,
+    pub win: f32
 }
 
 
@@ -48,7 +111,7 @@ pub struct FieldTypeData{
     pub path_level: u32,
     pub characteristic: FieldCharacteristic,
 
-    pub requirments: Vec<FieldRequirment>,
+    pub requirments: Vec<Condition<FieldRequirment>>,
     pub cost: HashMap<GResource, u32>,
 
     pub instant_effects: Vec<FieldInstantEffect>,
@@ -61,10 +124,49 @@ pub struct FieldTypeData{
     
     pub possible_actions: Vec<String>,
 
+    /// Given every turn while the building is active.
+    #[serde(default)]
+    pub production: HashMap<GResource, u32>,
+    #[serde(default)]
+    pub conditional_production: Vec<Production>,
+    /// Paid every turn. A building that can not be paid for is inactive
+    /// and produces nothing until it is paid again.
+    #[serde(default)]
+    pub upkeep: HashMap<GResource, u32>,
+    /// Turns the building is under construction, 0 builds it at once.
+    #[serde(default)]
+    pub build_time: u32,
+    /// What happens to the content the building replaces.
+    #[serde(default)]
+    pub replacement: Replacement,
+
     pub color: (u8, u8, u8)
 
 }
 
+/// Applied when the building is put on content it `Replaces` - not when it
+/// is the next level of the content's path.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Replacement{
+    /// Takes back resources given by the instant effects of the replaced
+    /// content and lowers the max heroes it raised. Effects played only the
+    /// first time are kept.
+    #[serde(default)]
+    pub undo_effects: bool,
+    /// Share of the replaced content's cost taken off the cost, rounded down.
+    #[serde(default)]
+    pub cost_discount: f32
+}
+
+/// Extra production when all `requirments` hold for the building, e.g.
+/// `HasOrigin` or `NearFieldWithId`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Production{
+    pub resource: GResource,
+    pub amount: u32,
+    pub requirments: Vec<Condition<FieldRequirment>>
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ActionData{
     pub action_family: String,
@@ -72,7 +174,7 @@ pub struct ActionData{
     pub modificators: HashMap<HeroSkill, f32>,
     pub points: HashMap<HeroSkill, (f32,f32)>,
     pub bonus_points: Vec<(ActionRequirment, HashMap<HeroSkill, (f32,f32)>)>,
-    pub requirments: Vec<ActionRequirment>,
+    pub requirments: Vec<Condition<ActionRequirment>>,
 
     pub eternal_modificator: EternalModificator,
 
@@ -81,6 +183,25 @@ pub struct ActionData{
 }
 
 
+/// Market offer: `take` bought for `give`. Every bought unit raises the
+/// price by `demand_step` of the base rate, and every turn the raise drops
+/// by `recovery` of itself.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExchangeData{
+    pub give: GResource,
+    pub take: GResource,
+    /// Units of `give` for one unit of `take`.
+    pub base_rate: f32,
+    /// Offer is open only with a building on one of these paths, always
+    /// when empty.
+    pub required_paths: Vec<String>,
+    /// Rate multiplier for every such building after the first one.
+    pub building_discount: f32,
+    pub demand_step: f32,
+    pub recovery: f32
+}
+
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct HeroData{
     pub hero_family: String,
@@ -117,88 +238,186 @@ pub struct QuestDecision{
     pub min_required_points: f32,
     pub points_50p: f32,
     pub additional_points: Vec<PointRequirment>,
-    pub required: Vec<Vec<PointRequirment>>,
+    pub required: Vec<Vec<Condition<PointRequirment>>>,
 
     pub treasure: HashMap<GResource, (f32, f32)>
 }
 
 //
 
-impl Resource<FieldTypeData> {
-    
-    pub fn is_possible_to_be_build(&self, origin: &Resource<OriginFieldData>, old: &Option<Rc<Resource<FieldTypeData>>>, near: &Vec<Vec<Rc<Resource<FieldTypeData>>>>, quest_completed: bool) -> bool{
-        let is_next_in_build_path = if let Some(d) = old{
-            d.data.build_path == self.data.build_path && d.data.path_level as i32 == self.data.path_level as i32 - 1
-        }else{
-            false
+impl QuestDecision {
+
+    /// No chance below `min_required_points`, 50% at `points_50p`, growing
+    /// towards certainty with more points.
+    pub fn success_probability(&self, points: f32) -> f32 {
+        if points < self.min_required_points || points <= 0.0 {
+            0.0
+        } else {
+            points / (points + self.points_50p)
+        }
+    }
+}
+
+/// Contents of tiles at distance 0, 1, 2... from a tile, with positions.
+pub type NearContents = [Vec<(TilePos, Rc<Resource<FieldTypeData>>)>];
+
+impl FieldRequirment {
+    /// `Replaces` is always met here, it is checked against the old content
+    /// on its own. `Err` says why the requirment is not met, e.g.
+    /// `NoNearFieldWithPath town within 5: violated by village at (9,18)`.
+    pub fn check(&self, origin: &Resource<OriginFieldData>, near: &NearContents, quest_completed: bool) -> Result<(), String>{
+        let find = |distance: u32, f: &dyn Fn(&Resource<FieldTypeData>) -> bool|near.iter()
+            .skip(1)
+            .take(distance as usize)
+            .flat_map(|e|e.iter())
+            .find(|n|f(&n.1))
+            .map(|n|format!("{} at ({},{})", n.1.id, n.0.q, n.0.r));
+        let near_field = |distance: u32, f: &dyn Fn(&Resource<FieldTypeData>) -> bool|match find(distance, f) {
+            Some(_) => Ok(()),
+            None => Err(format!("{}: nothing matching", self)),
+        };
+        let no_near_field = |distance: u32, f: &dyn Fn(&Resource<FieldTypeData>) -> bool|match find(distance, f) {
+            Some(found) => Err(format!("{}: violated by {}", self, found)),
+            None => Ok(()),
         };
+        match self {
+            FieldRequirment::CantBuild => Err("CantBuild: never built by the player".to_string()),
+            FieldRequirment::CantDemolish => Ok(()),
+            FieldRequirment::Replaces(_) |
+            FieldRequirment::ReplacesPath(_) |
+            FieldRequirment::ReplacesCharacteristic(_) => Ok(()),   //replacement checked in first order
+
+            FieldRequirment::HasOrigin(id) => match origin.id == *id {
+                true => Ok(()),
+                false => Err(format!("{}: origin is {}", self, origin.id)),
+            },
+            FieldRequirment::QuestCompleted => match quest_completed {
+                true => Ok(()),
+                false => Err("QuestCompleted: no quest completed here since the last build".to_string()),
+            },
+            FieldRequirment::NearFieldWithCharacteristic { characteristic, distance } =>
+                near_field(*distance, &|n|n.data.characteristic == *characteristic),
+            FieldRequirment::NearFieldWithPath { build_path, distance } =>
+                near_field(*distance, &|n|n.data.build_path == *build_path),
+            FieldRequirment::NearFieldWithId { id, distance } =>
+                near_field(*distance, &|n|n.id == *id),
+            FieldRequirment::NoNearFieldWithCharacteristic { characteristic, distance } =>
+                no_near_field(*distance, &|n|n.data.characteristic == *characteristic),
+            FieldRequirment::NoNearFieldWithPath { build_path, distance } =>
+                no_near_field(*distance, &|n|n.data.build_path == *build_path),
+            FieldRequirment::NoNearFieldWithId { id, distance } =>
+                no_near_field(*distance, &|n|n.id == *id),
+            FieldRequirment::HasOriginOneOf(v) => match v.contains(&origin.id) {
+                true => Ok(()),
+                false => Err(format!("{}: origin is {}", self, origin.id)),
+            },
+        }
+    }
+
+    /// Whether this is one of the `Replaces` requirments.
+    pub fn is_replacement(&self) -> bool{
+        matches!(self, FieldRequirment::Replaces(_) | FieldRequirment::ReplacesPath(_) | FieldRequirment::ReplacesCharacteristic(_))
+    }
 
-        let is_possible_replacement_for_building = self.data.requirments.iter()
-            .find(|e|{
-                match e {
-                    FieldRequirment::Replaces(id) => self.id == *id,
-                    _ => false
-                }
-            }).is_some();
-
-        if old.is_some() && ! (is_next_in_build_path || is_possible_replacement_for_building) {
-            return false;
-        } else if old.is_none() && self.data.path_level != 0 {
-            return false;
+    /// Whether this is a `Replaces` requirment matching `old`.
+    pub fn allows_replacing(&self, old: &Resource<FieldTypeData>) -> bool{
+        match self {
+            FieldRequirment::Replaces(id) => old.id == *id,
+            FieldRequirment::ReplacesPath(build_path) => old.data.build_path == *build_path,
+            FieldRequirment::ReplacesCharacteristic(characteristic) => old.data.characteristic == *characteristic,
+            _ => false
         }
+    }
+}
 
-        self.data.requirments
-            .iter()
-            .all(|r|{
-                match r {
-                    FieldRequirment::CantBuild => false,
-                    FieldRequirment::Replaces(_) => true,   //replacement checked in first order
-
-                    FieldRequirment::HasOrigin(id) => origin.id == *id,
-                    FieldRequirment::QuestCompleted => quest_completed,
-                    FieldRequirment::NearFieldWithCharacteristic { characteristic, distance } => {
-                        near.iter().skip(1).take(*distance as usize).flat_map(|e|e.iter())
-                        .find(|n|n.data.characteristic == *characteristic).is_some()
-                    },
-                    FieldRequirment::NearFieldWithPath { build_path, distance } => {
-                        near.iter().skip(1).take(*distance as usize).flat_map(|e|e.iter())
-                        .find(|n|n.data.build_path == *build_path).is_some()
-                    },
-                    FieldRequirment::NearFieldWithId { id, distance } => {
-                        near.iter().skip(1).take(*distance as usize).flat_map(|e|e.iter())
-                        .find(|n|n.id == *id).is_some()
-                    },
-                    FieldRequirment::NoNearFieldWithCharacteristic { characteristic, distance } =>{
-                        near.iter().skip(1).take(*distance as usize).flat_map(|e|e.iter())
-                        .find(|n|n.data.characteristic == *characteristic).is_none()
-                    },
-                    FieldRequirment::NoNearFieldWithPath { build_path, distance } => {
-                        near.iter().skip(1).take(*distance as usize).flat_map(|e|e.iter())
-                        .find(|n|n.data.build_path == *build_path).is_none()
-                    },
-                    FieldRequirment::NoNearFieldWithId { id, distance } => {
-                        near.iter().skip(1).take(*distance as usize).flat_map(|e|e.iter())
-                        .find(|n|n.id == *id).is_none()
-                    },
-                    FieldRequirment::HasOriginOneOf(v) => {
-                        v.iter().find(|e|origin.id == **e).is_some()
-                    },
-                }
-            })
+impl Resource<FieldTypeData> {
+
+    pub fn is_possible_to_be_build(&self, origin: &Resource<OriginFieldData>, old: &Option<Rc<Resource<FieldTypeData>>>, near: &NearContents, quest_completed: bool, facts: &ConditionFacts) -> bool{
+        self.check_build(origin, old, near, quest_completed, facts).is_ok()
     }
 
+    /// `is_possible_to_be_build` with the reason when it is not.
+    pub fn check_build(&self, origin: &Resource<OriginFieldData>, old: &Option<Rc<Resource<FieldTypeData>>>, near: &NearContents, quest_completed: bool, facts: &ConditionFacts) -> Result<(), String>{
+        self.check_placement(old)?;
+        self.check_effects()?;
+        check_all(&self.data.requirments, facts, &|r|r.check(origin, near, quest_completed))
+    }
 
+    /// Whether the building can be put on a tile with `old` content - as
+    /// the next level of its path or as a replacement.
+    pub fn check_placement(&self, old: &Option<Rc<Resource<FieldTypeData>>>) -> Result<(), String>{
+        let is_next_in_build_path = old.as_ref().is_some_and(|e|self.is_upgrade_of(e));
+
+        if let Some(old) = old {
+            if !(is_next_in_build_path || self.replaces(old)) {
+                return Err(format!("can not be built on {}", old.id));
+            }
+        } else if self.data.path_level != 0 {
+            return Err(format!("needs level {} of {} to upgrade", self.data.path_level - 1, self.data.build_path));
+        }
+        Ok(())
+    }
+
+    /// Whether the game can play every instant effect of the building.
+    pub fn check_effects(&self) -> Result<(), String>{
+        match self.data.instant_effects.iter().any(|e|matches!(e, FieldInstantEffect::LearnAboutAction { .. })) {
+            true => Err("LearnAboutAction effect is not supported yet".to_string()),
+            false => Ok(()),
+        }
+    }
+
+    /// Whether the building is the next level of the path of `old`.
+    pub fn is_upgrade_of(&self, old: &Resource<FieldTypeData>) -> bool{
+        old.data.build_path == self.data.build_path && old.data.path_level as i32 == self.data.path_level as i32 - 1
+    }
+
+    /// Production of the building on a tile with given origin, neighbours
+    /// and quest state - `production` and every met `conditional_production`.
+    pub fn get_production(&self, origin: &Resource<OriginFieldData>, near: &NearContents, quest_completed: bool, facts: &ConditionFacts) -> HashMap<GResource, u32>{
+        let mut res = self.data.production.clone();
+        for production in self.data.conditional_production.iter() {
+            if check_all(&production.requirments, facts, &|r|r.check(origin, near, quest_completed)).is_ok() {
+                *res.entry(production.resource).or_insert(0) += production.amount;
+            }
+        }
+        res
+    }
+
+    /// Whether the building has the requirment, outside of any `Any` or `Not`.
+    pub fn has_requirment(&self, f: impl Fn(&FieldRequirment) -> bool) -> bool{
+        self.data.requirments.iter().flat_map(|e|e.required()).any(f)
+    }
+
+    /// Whether one of the `Replaces` requirments allows building on `old`.
+    pub fn replaces(&self, old: &Resource<FieldTypeData>) -> bool{
+        self.has_requirment(|e|e.allows_replacing(old))
+    }
+
+    /// Cost of building on a tile with `old` content, lowered by
+    /// `Replacement::cost_discount` when it is replaced.
+    pub fn get_cost(&self, old: &Option<Rc<Resource<FieldTypeData>>>) -> HashMap<GResource, u32>{
+        let discount = match old {
+            Some(old) if self.replaces(old) => self.data.replacement.cost_discount.clamp(0.0, 1.0),
+            _ => return self.data.cost.clone(),
+        };
+        self.data.cost.iter()
+            .map(|(resource, amount)|{
+                let off = (*old.as_ref().unwrap().data.cost.get(resource).unwrap_or(&0) as f32 * discount).floor() as u32;
+                (*resource, amount.saturating_sub(off))
+            })
+            .collect()
+    }
 
-    pub fn has_enough_resources(&self, gres: &HashMap<GResource, u32>) -> bool{
-        self.data.cost
+    pub fn has_enough_resources(&self, gres: &HashMap<GResource, u32>, old: &Option<Rc<Resource<FieldTypeData>>>) -> bool{
+        self.get_cost(old)
             .iter()
             .all(|e|{
                 gres.get(e.0).map_or(false, |g| g >= e.1)
             })
     }
 
-    pub fn spent_resources(&self, gres: &mut HashMap<GResource, u32>) {
-        self.data.cost
+    pub fn spent_resources(&self, gres: &mut HashMap<GResource, u32>, old: &Option<Rc<Resource<FieldTypeData>>>) {
+        self.get_cost(old)
             .iter()
             .for_each(|e|{
                 *gres.get_mut(e.0).unwrap() -= e.1;
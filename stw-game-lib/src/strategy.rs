@@ -0,0 +1,118 @@
+//! Bots for automated playtesting. A `Strategy` picks the next move, `autoplay`
+//! keeps asking it until the turn limit.
+
+mod random;
+mod greedy_builder;
+mod quest_planner;
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{game::{STWGame, game_move::GameMove, game_controller::GameCallback}, resource::enums::GResource};
+
+pub use self::{random::RandomStrategy, greedy_builder::GreedyBuilder, quest_planner::QuestPlanner};
+
+/// Moves allowed in a single turn before `autoplay` gives up on a strategy.
+const MAX_MOVES_PER_TURN: u32 = 1000;
+
+pub trait Strategy {
+    fn choose(&self, game: &STWGame) -> GameMove;
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", content = "data")]
+pub enum StopReason{
+    MaxTurns,
+    GameOver,
+    NoLegalMoves,
+    IllegalMove{game_move: GameMove, msg: String},
+    MoveLimit
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AutoplaySummary{
+    pub turns: u32,
+    pub moves: u32,
+    pub resources: BTreeMap<GResource, u32>,
+    /// Number of tiles with each content at the end of the game.
+    pub buildings: BTreeMap<String, u32>,
+    pub heroes: u32,
+    pub quests_succeeded: u32,
+    pub quests_failed: u32,
+    pub score: u32,
+    pub stop_reason: StopReason
+}
+
+/// Plays `max_turns` turns (counted from the current one) with moves from
+/// `strategy`. Stops early when the strategy picks a move the game rejects.
+pub fn autoplay(game: &mut STWGame, strategy: &dyn Strategy, max_turns: u32) -> AutoplaySummary{
+    autoplay_with(game, strategy, max_turns, &mut |_, _|{})
+}
+
+/// `autoplay` that shows `observer` the game and callbacks after every move.
+pub fn autoplay_with(game: &mut STWGame, strategy: &dyn Strategy, max_turns: u32, observer: &mut dyn FnMut(&STWGame, &[GameCallback])) -> AutoplaySummary{
+    let last_turn = game.get_turn() + max_turns;
+    let mut moves = 0;
+    let mut moves_this_turn = 0;
+    let mut quests = (0, 0);
+
+    let stop_reason = loop {
+        if game.get_turn() >= last_turn {
+            break StopReason::MaxTurns;
+        }
+        if moves_this_turn >= MAX_MOVES_PER_TURN {
+            break StopReason::MoveLimit;
+        }
+        if game.is_game_over() {
+            break StopReason::GameOver;
+        }
+        if game.get_possible_moves().is_empty() {
+            break StopReason::NoLegalMoves;
+        }
+        let game_move = strategy.choose(game);
+        let turn = game.get_turn();
+        match game.perform_move(&game_move) {
+            Ok(callbacks) => {
+                callbacks.iter().for_each(|e|match e {
+                    GameCallback::QuestFinished { success: true, .. } => quests.0 += 1,
+                    GameCallback::QuestFinished { success: false, .. } => quests.1 += 1,
+                    _ => {}
+                });
+                observer(game, &callbacks);
+            },
+            Err(e) => break StopReason::IllegalMove { game_move, msg: e.msg },
+        }
+        moves += 1;
+        moves_this_turn = if game.get_turn() == turn { moves_this_turn + 1 } else { 0 };
+    };
+
+    let state = game.get_state();
+    let mut buildings = BTreeMap::new();
+    state.tiles.iter()
+        .filter_map(|e|e.content.as_ref())
+        .for_each(|e|*buildings.entry(e.to_string()).or_insert(0) += 1);
+    AutoplaySummary {
+        turns: state.turn,
+        moves,
+        resources: state.resources,
+        buildings,
+        heroes: state.heroes.len() as u32,
+        quests_succeeded: quests.0,
+        quests_failed: quests.1,
+        score: game.get_score(),
+        stop_reason
+    }
+}
+
+/// Move for strategies with no preference: finish the quest if possible,
+/// otherwise wait, otherwise anything legal.
+pub(crate) fn fallback_move(game: &STWGame) -> GameMove{
+    let legal = game.get_possible_moves();
+    legal.iter()
+        .find(|e|matches!(e, GameMove::MakeDecision(_)))
+        .or_else(||legal.iter().find(|e|**e == GameMove::Wait))
+        .or(legal.first())
+        .cloned()
+        .unwrap_or(GameMove::Wait)
+}
@@ -0,0 +1,58 @@
+use crate::{game::{STWGame, game_move::GameMove}, resource::resource_data::FieldTypeData};
+
+use super::{Strategy, fallback_move};
+
+/// Builds the best affordable building each turn and waits when there is
+/// none. Never starts quests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GreedyBuilder{
+    /// Score per `path_level` of the building.
+    pub path_weight: f32,
+    /// Added for buildings on `Globals::win_cond_build_path`.
+    pub win_path_bonus: f32,
+    /// Subtracted per unit of any resource in `FieldTypeData::cost`.
+    pub cost_weight: f32
+}
+
+impl Default for GreedyBuilder {
+    fn default() -> Self {
+        GreedyBuilder { path_weight: 1.0, win_path_bonus: 2.0, cost_weight: 0.1 }
+    }
+}
+
+impl GreedyBuilder {
+    pub fn score(&self, game: &STWGame, building: &FieldTypeData) -> f32{
+        let win_path = &game.get_resource_manager().get_globals().data.win_cond_build_path;
+        let bonus = if building.build_path == *win_path { self.win_path_bonus } else { 0.0 };
+        building.path_level as f32 * self.path_weight + bonus
+            - building.cost.values().sum::<u32>() as f32 * self.cost_weight
+    }
+
+    /// Best legal `Build` move, `None` when nothing can be built.
+    pub fn best_build(&self, game: &STWGame) -> Option<GameMove>{
+        let rm = game.get_resource_manager();
+        let mut best: Option<(f32, GameMove)> = None;
+        for game_move in game.get_possible_moves() {
+            let GameMove::Build(_, id) = &game_move else {
+                continue;
+            };
+            let Ok(building) = rm.get_resource::<FieldTypeData>(id) else {
+                continue;
+            };
+            let score = self.score(game, &building.data);
+            if best.as_ref().is_none_or(|e|score > e.0) {
+                best = Some((score, game_move));
+            }
+        }
+        best.map(|e|e.1)
+    }
+}
+
+impl Strategy for GreedyBuilder {
+    fn choose(&self, game: &STWGame) -> GameMove {
+        if game.get_history().is_some() {
+            return fallback_move(game);
+        }
+        self.best_build(game).unwrap_or_else(||fallback_move(game))
+    }
+}
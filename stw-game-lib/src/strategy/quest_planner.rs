@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use crate::{game::{STWGame, game_move::GameMove, map::TilePos, planner::{PlannedRoute, HeroRecommendation}}, resource::resource_data::QuestDecision};
+
+use super::{Strategy, GreedyBuilder, fallback_move};
+
+/// Sends heroes on quests and walks them along the planned route of the
+/// decision with the best chance. Builds like `GreedyBuilder` when there is
+/// no quest to take.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuestPlanner{
+    /// Decision is made as soon as its success chance reaches this value.
+    pub decision_threshold: f32,
+    pub builder: GreedyBuilder
+}
+
+impl Default for QuestPlanner {
+    fn default() -> Self {
+        QuestPlanner::new(0.75)
+    }
+}
+
+impl QuestPlanner {
+    pub fn new(decision_threshold: f32) -> QuestPlanner{
+        QuestPlanner { decision_threshold, builder: GreedyBuilder::default() }
+    }
+
+    fn choose_in_history(&self, game: &STWGame) -> GameMove{
+        let Some(history) = game.get_history() else {
+            return fallback_move(game);
+        };
+        let Some(quest) = game.get_quests().get(&history.quest_pos) else {
+            return fallback_move(game);
+        };
+        let hero = &game.get_heroes()[history.hero_index];
+        let quest_type = quest.get_quest_type();
+        let chance = |decision: &QuestDecision|decision.success_probability(history.get_decision_points(decision, hero));
+
+        let mut best_decision: Option<(f32, &QuestDecision)> = None;
+        for id in game.get_possible_decisions() {
+            if let Some(decision) = quest_type.data.quest_decisions.iter().find(|e|e.id == id) {
+                if best_decision.is_none_or(|e|chance(decision) > e.0) {
+                    best_decision = Some((chance(decision), decision));
+                }
+            }
+        }
+        let moves = game.get_possible_hero_moves();
+        if let Some((probability, decision)) = best_decision {
+            if moves.is_empty() || probability >= self.decision_threshold {
+                return GameMove::MakeDecision(decision.id.to_string());
+            }
+        }
+
+        // aim at the decision whose best route gives the best chance, it could be not yet open
+        let mut best_route: Option<PlannedRoute> = None;
+        for decision in &quest_type.data.quest_decisions {
+            if let Some(route) = game.best_route(&decision.id) {
+                if best_route.as_ref().is_none_or(|e|route.decision.success_probability > e.decision.success_probability) {
+                    best_route = Some(route);
+                }
+            }
+        }
+        match best_route.and_then(|e|e.moves.into_iter().next()) {
+            Some((pos, id)) => GameMove::PlayMove(pos, id),
+            None => best_decision.map_or_else(||fallback_move(game), |e|GameMove::MakeDecision(e.1.id.to_string())),
+        }
+    }
+}
+
+impl Strategy for QuestPlanner {
+    fn choose(&self, game: &STWGame) -> GameMove {
+        if game.get_history().is_some() {
+            return self.choose_in_history(game);
+        }
+        let mut rankings: HashMap<TilePos, Vec<HeroRecommendation>> = HashMap::new();
+        let mut best_start: Option<(f32, GameMove)> = None;
+        for game_move in game.get_possible_moves() {
+            let GameMove::StartHistory(pos, hero) = &game_move else {
+                continue;
+            };
+            let ranking = rankings.entry(*pos)
+                .or_insert_with(||game.rank_heroes_for_quest(pos).unwrap_or_default());
+            let chance = ranking.iter()
+                .find(|e|e.hero_index == *hero)
+                .map_or(0.0, |e|e.route.decision.success_probability);
+            if best_start.as_ref().is_none_or(|e|chance > e.0) {
+                best_start = Some((chance, game_move));
+            }
+        }
+        match best_start {
+            Some((_, game_move)) => game_move,
+            None => self.builder.choose(game),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use std::cell::RefCell;
+
+use rand::seq::SliceRandom;
+use rand_pcg::Pcg64;
+use rand_seeder::Seeder;
+
+use crate::game::{STWGame, game_move::GameMove};
+
+use super::Strategy;
+
+/// Picks uniformly from the legal moves.
+pub struct RandomStrategy{
+    rng: RefCell<Pcg64>
+}
+
+impl RandomStrategy {
+    pub fn new(seed: &str) -> RandomStrategy{
+        RandomStrategy { rng: RefCell::new(Seeder::from((seed, "strategy")).make_rng()) }
+    }
+}
+
+impl Strategy for RandomStrategy {
+    fn choose(&self, game: &STWGame) -> GameMove {
+        game.get_possible_moves()
+            .choose(&mut *self.rng.borrow_mut())
+            .cloned()
+            .unwrap_or(GameMove::Wait)
+    }
+}
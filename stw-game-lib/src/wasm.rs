@@ -0,0 +1,120 @@
+//! JS bindings for a browser build without a server. Build with
+//! `--no-default-features --features wasm` for `wasm32-unknown-unknown`.
+//! Moves, callbacks and states are passed as plain JS objects in the shape
+//! described in `docs/json-protocol.md`.
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{game::{STWGame, map::TilePos, game_move::GameMove}, resource::{parse_resources, default_resources}, GameConfig};
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsError>{
+    value.serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e|JsError::new(&e.to_string()))
+}
+
+#[wasm_bindgen(js_name = GameConfig)]
+pub struct JsGameConfig{
+    resources: Vec<(String, serde_json::Value)>,
+    name: Option<String>,
+    seed: String,
+    map_size: Option<u32>
+}
+
+#[wasm_bindgen(js_class = GameConfig)]
+impl JsGameConfig {
+    /// Config with the embedded default resources and a seed from `Math.random`.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Result<JsGameConfig, JsError>{
+        Ok(JsGameConfig {
+            resources: default_resources().map_err(|e|JsError::new(&e.msg))?,
+            name: None,
+            seed: format!("{:016x}", (js_sys::Math::random() * u64::MAX as f64) as u64),
+            map_size: None
+        })
+    }
+
+    /// Replaces the default resources with a json array of `{"type", "resource"}` entries.
+    #[wasm_bindgen(js_name = setResources)]
+    pub fn set_resources(&mut self, json: &str) -> Result<(), JsError>{
+        self.resources = parse_resources(json).map_err(|e|JsError::new(&e.msg))?;
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = setName)]
+    pub fn set_name(&mut self, name: &str){
+        self.name = Some(name.to_string());
+    }
+
+    #[wasm_bindgen(js_name = setSeed)]
+    pub fn set_seed(&mut self, seed: &str){
+        self.seed = seed.to_string();
+    }
+
+    #[wasm_bindgen(js_name = setMapSize)]
+    pub fn set_map_size(&mut self, map_size: u32){
+        self.map_size = Some(map_size);
+    }
+
+    #[wasm_bindgen(js_name = startGame)]
+    pub fn start_game(&self) -> Result<JsGame, JsError>{
+        let mut config = GameConfig::new()
+            .set_resources(self.resources.clone())
+            .set_seed(&self.seed);
+        if let Some(name) = &self.name {
+            config = config.set_name(name);
+        }
+        if let Some(map_size) = self.map_size {
+            config = config.set_map_size(map_size);
+        }
+        let game = config.start_game().map_err(|e|JsError::new(&e.msg))?;
+        Ok(JsGame { game })
+    }
+}
+
+#[wasm_bindgen(js_name = STWGame)]
+pub struct JsGame{
+    game: STWGame
+}
+
+#[wasm_bindgen(js_class = STWGame)]
+impl JsGame {
+    /// Returns `{callbacks: [...]}` or `{bad_move: {msg}}` when the move is not allowed.
+    #[wasm_bindgen(js_name = performMove)]
+    pub fn perform_move(&mut self, game_move: JsValue) -> Result<JsValue, JsError>{
+        let game_move: GameMove = serde_wasm_bindgen::from_value(game_move)
+            .map_err(|e|JsError::new(&format!("Can not parse move: {}", e)))?;
+        match self.game.perform_move(&game_move) {
+            Ok(callbacks) => to_js(&serde_json::json!({"callbacks": callbacks})),
+            Err(e) => to_js(&serde_json::json!({"bad_move": e})),
+        }
+    }
+
+    #[wasm_bindgen(js_name = possibleBuildings)]
+    pub fn possible_buildings(&self, q: i32, r: i32) -> Result<JsValue, JsError>{
+        to_js(&self.game.get_possible_building_at_position(&TilePos { q, r }))
+    }
+
+    /// Array of `[position, action]` pairs for the hero on a quest.
+    #[wasm_bindgen(js_name = possibleHeroMoves)]
+    pub fn possible_hero_moves(&self) -> Result<JsValue, JsError>{
+        to_js(&self.game.get_possible_hero_moves())
+    }
+
+    #[wasm_bindgen(js_name = legalMoves)]
+    pub fn legal_moves(&self) -> Result<JsValue, JsError>{
+        to_js(&self.game.get_possible_moves())
+    }
+
+    /// Tile state at `(q, r)`, `undefined` outside of the map.
+    pub fn tile(&self, q: i32, r: i32) -> Result<JsValue, JsError>{
+        match self.game.get_tile_state(&TilePos { q, r }) {
+            Some(tile) => to_js(&tile),
+            None => Ok(JsValue::UNDEFINED),
+        }
+    }
+
+    pub fn state(&self) -> Result<JsValue, JsError>{
+        to_js(&self.game.get_state())
+    }
+}
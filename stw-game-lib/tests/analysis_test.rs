@@ -0,0 +1,111 @@
+mod common;
+use serde_json::json;
+use stw_game_lib::resource::{analysis::{analyze_resources, GraphNode, GraphEdge, EdgeKind}, enums::GResource};
+use crate::common::load_resources;
+
+fn building(id: &str, build_path: &str, path_level: u32, requirments: serde_json::Value, cost: serde_json::Value) -> (String, serde_json::Value){
+    ("FieldTypeData".to_string(), json!({
+        "id": id,
+        "data": {
+            "build_path": build_path,
+            "path_level": path_level,
+            "characteristic": "Habited",
+            "requirments": requirments,
+            "cost": cost,
+            "instant_effects": [],
+            "quest_family": "",
+            "quest_levels": [],
+            "hero_family": "",
+            "hero_levels": [],
+            "possible_actions": [],
+            "color": [0, 0, 0]
+        }
+    }))
+}
+
+fn small_resources() -> Vec<(String, serde_json::Value)>{
+    vec![
+        ("Globals".to_string(), json!({
+            "id": "globals",
+            "data": {
+                "init_path_length_per_hero": 3,
+                "start_game_resources": {"Gold": 5},
+                "map_frequency": 0.3,
+                "map_middle": "hall",
+                "map_near_mid_enable": ["meadow"],
+                "map_visible_on_start": 3,
+                "win_cond_build_path": "town"
+            }
+        })),
+        ("OriginFieldData".to_string(), json!({"id": "meadow", "data": {"height": 0.6, "vegetation": 0.4, "possible_actions": [], "color": [0, 0, 0]}})),
+        ("OriginFieldData".to_string(), json!({"id": "hills", "data": {"height": 0.8, "vegetation": 1.0, "possible_actions": [], "color": [0, 0, 0]}})),
+        building("hall", "town", 0, json!(["CantBuild"]), json!({})),
+        building("farm", "farm", 0, json!([{"HasOrigin": "meadow"}, {"NearFieldWithPath": {"build_path": "town", "distance": 2}}]), json!({"Gold": 2})),
+        building("big_farm", "farm", 1, json!([]), json!({"Gold": 4})),
+        building("mine", "farm", 2, json!([{"HasOrigin": "hills"}]), json!({"Gold": 4})),
+        building("temple", "temple", 0, json!([]), json!({"EpicTrophy": 1})),
+        building("hermit", "hermit", 0, json!([
+            {"NearFieldWithCharacteristic": {"characteristic": "Habited", "distance": 2}},
+            {"NoNearFieldWithCharacteristic": {"characteristic": "Habited", "distance": 3}}
+        ]), json!({}))
+    ]
+}
+
+#[test]
+fn analysis_finds_unreachable_buildings() {
+    let res = analyze_resources(&small_resources()).unwrap();
+
+    assert_eq!(res.reachable_buildings.iter().collect::<Vec<_>>(), vec!["big_farm", "farm", "hall"]);
+    assert_eq!(res.unreachable_buildings["mine"], vec![r#"big_farm can not stand on any of the allowed origins {"hills"}"#]);
+    assert_eq!(res.unreachable_buildings["temple"], vec!["costs 1 EpicTrophy which can not be obtained"]);
+    assert_eq!(res.unreachable_buildings["hermit"].len(), 1);
+    assert!(res.unreachable_buildings["hermit"][0].contains("has no reachable building to match"));
+
+    assert_eq!(res.obtainable_resources.iter().collect::<Vec<_>>(), vec![&GResource::Gold]);
+    assert_eq!(res.unobtainable_resources[&GResource::EpicTrophy], vec!["temple"]);
+    assert_eq!(res.dead_end_paths["farm"], "stops at level 1 of 2");
+    assert_eq!(res.dead_end_paths["temple"], "no level of 1 is reachable");
+    assert!(!res.dead_end_paths.contains_key("town"));
+    assert!(res.edges.contains(&GraphEdge {
+        from: GraphNode::Building("big_farm".to_string()),
+        to: GraphNode::Building("mine".to_string()),
+        kind: EdgeKind::Upgrade
+    }));
+}
+
+#[test]
+fn analysis_reports_data_problems_in_default_resources() {
+    let res = analyze_resources(&load_resources()).unwrap();
+
+    assert!(res.unreachable_buildings.is_empty(), "{:?}", res.unreachable_buildings);
+    assert!(res.obtainable_resources.contains(&GResource::GreenTrophy));
+    // only from the market
+    assert!(res.obtainable_resources.contains(&GResource::RareTrophy));
+    assert!(res.edges.contains(&GraphEdge {
+        from: GraphNode::Resource(GResource::RedTrophy),
+        to: GraphNode::Resource(GResource::RareTrophy),
+        kind: EdgeKind::Exchange
+    }));
+    assert!(!res.spawnable_quests.contains("explore_caves"));
+    for problem in [
+        "Duplicate QuestData id explore_caves (2 times) - only the last one is used",
+        "Building wilderness offers missing action exploration_wilderness",
+        "Building bandit_camp_small offers missing action fight_with_bandits_small",
+        "Building caves spawns quests of family mountains level 0 but there are none",
+        "Quest explore_caves (family caves level 1) is never spawned by any building"
+    ] {
+        assert!(res.problems.iter().any(|e|e == problem), "{} not in {:#?}", problem, res.problems);
+    }
+}
+
+#[test]
+fn analysis_exports_dot() {
+    let dot = analyze_resources(&small_resources()).unwrap().to_dot();
+
+    assert!(dot.starts_with("digraph stw {\n"));
+    assert!(dot.ends_with("}\n"));
+    assert!(dot.contains(r#""building:mine" [label="mine", shape=box, color=red, style=dashed];"#));
+    assert!(dot.contains(r#""building:farm" [label="farm", shape=box];"#));
+    assert!(dot.contains(r#""resource:Gold" -> "building:farm" [label="cost 2"];"#));
+    assert!(dot.contains(r#""building:farm" -> "building:big_farm" [label="upgrade"];"#));
+}
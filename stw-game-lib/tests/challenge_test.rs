@@ -0,0 +1,64 @@
+mod common;
+use std::{env, fs};
+
+use stw_game_lib::{challenge::{Challenge, Leaderboard}, game::game_move::GameMove, strategy::{autoplay, QuestPlanner}};
+use crate::common::load_resources;
+
+#[test]
+fn daily_challenge_is_the_same_for_everyone() {
+    let resources = load_resources();
+    let first = Challenge::daily("2024-05-01", &resources).unwrap();
+    assert_eq!(first, Challenge::daily("2024-05-01", &resources).unwrap());
+    assert_ne!(first.seed, Challenge::daily("2024-05-02", &resources).unwrap().seed);
+    assert!(Challenge::daily("2024-13-01", &resources).is_err());
+    assert!(Challenge::daily("yesterday", &resources).is_err());
+
+    let a = first.start_game(resources.clone()).unwrap();
+    let b = first.start_game(resources.clone()).unwrap();
+    assert_eq!(a.get_state(), b.get_state());
+    assert!(first.start_game(resources[1..].to_vec()).is_err());
+}
+
+#[test]
+fn verifier_rejects_wrong_scores() {
+    let resources = load_resources();
+    let challenge = Challenge::daily("2024-05-01", &resources).unwrap();
+    let mut game = challenge.start_game(resources.clone()).unwrap();
+    autoplay(&mut game, &QuestPlanner::default(), 10);
+    let score = game.get_score();
+    let moves: Vec<GameMove> = game.get_replay().steps.iter().map(|e|e.game_move.clone()).collect();
+
+    assert_eq!(challenge.verify(&moves, score, resources.clone()).unwrap(), game.get_summary());
+    assert!(challenge.verify(&moves, score + 1, resources.clone()).is_err());
+    assert!(challenge.verify(&[GameMove::MakeDecision("cheat".to_string())], 0, resources.clone()).is_err());
+
+    let other = Challenge::daily("2024-05-02", &resources).unwrap();
+    assert!(other.verify_replay(game.get_replay(), score, resources).is_err());
+}
+
+#[test]
+fn leaderboard_keeps_verified_scores() {
+    let resources = load_resources();
+    let challenge = Challenge::daily("2024-05-01", &resources).unwrap();
+    let dir = env::temp_dir().join(format!("stw-leaderboard-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("leaderboard.json");
+
+    let mut leaderboard = Leaderboard::load(&path).unwrap();
+    for (player, turns) in [("short", 3), ("long", 15)] {
+        let mut game = challenge.start_game(resources.clone()).unwrap();
+        autoplay(&mut game, &QuestPlanner::default(), turns);
+        leaderboard.submit(&challenge, player, game.get_replay(), game.get_score(), resources.clone()).unwrap();
+    }
+    let mut game = challenge.start_game(resources.clone()).unwrap();
+    autoplay(&mut game, &QuestPlanner::default(), 5);
+    assert!(leaderboard.submit(&challenge, "cheater", game.get_replay(), game.get_score() + 10, resources.clone()).is_err());
+
+    let loaded = Leaderboard::load(&path).unwrap();
+    assert_eq!(loaded, leaderboard);
+    let entries = loaded.get_entries(&challenge.id);
+    assert_eq!(entries.len(), 2);
+    assert!(entries[0].score >= entries[1].score);
+    assert!(loaded.verify_entries(&challenge, resources).is_empty());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,6 +1,7 @@
 use std::{fs, env};
 
 use serde_json::{Value};
+use stw_game_lib::{game::{STWGame, map::TilePos}, resource::enums::GResource, GameConfig};
 
 
 pub fn load_resources() -> Vec<(String, serde_json::Value)>{
@@ -22,3 +23,34 @@ pub fn load_resources() -> Vec<(String, serde_json::Value)>{
     res
     
 }
+
+/// Game on the default resources, with `change` applied to the data of
+/// every resource first.
+#[allow(dead_code)]
+pub fn game_with(change: impl Fn(&str, &mut Value)) -> STWGame {
+    let mut resources = load_resources();
+    for resource in resources.iter_mut() {
+        let id = resource.1["id"].as_str().unwrap_or_default().to_string();
+        change(&id, &mut resource.1["data"]);
+    }
+    GameConfig::new()
+        .set_map_size(37)
+        .set_resources(resources)
+        .set_seed("test seed")
+        .start_game()
+        .unwrap()
+}
+
+#[allow(dead_code)]
+pub fn gold(game: &STWGame) -> u32 {
+    game.get_resources()[&GResource::Gold]
+}
+
+/// First tile where `id` can be built right now.
+#[allow(dead_code)]
+pub fn position_for(game: &STWGame, id: &str) -> TilePos {
+    game.get_state().tiles.iter()
+        .find(|e|game.get_possible_building_at_position(&e.position).iter().any(|b|b.id == id))
+        .unwrap()
+        .position
+}
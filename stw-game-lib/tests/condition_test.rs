@@ -0,0 +1,64 @@
+mod common;
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+use stw_game_lib::{game::{STWGame, game_move::GameMove}, resource::{condition::{Condition, ConditionFacts}, enums::{FieldRequirment, GResource}}};
+use crate::common::game_with;
+
+fn condition(value: Value) -> Condition<FieldRequirment> {
+    serde_json::from_value(value).unwrap()
+}
+
+/// Leaf check of origin requirements only, the tile is a forest.
+fn forest(requirment: &FieldRequirment) -> Result<(), String> {
+    match requirment {
+        FieldRequirment::HasOrigin(id) if id != "forest" => Err(format!("origin is forest, not {}", id)),
+        _ => Ok(()),
+    }
+}
+
+#[test]
+fn plain_requirments_parse_as_conditions() {
+    assert!(matches!(condition(json!("CantBuild")).as_requirment(), Some(FieldRequirment::CantBuild)));
+    assert!(matches!(condition(json!({"HasOrigin": "forest"})).as_requirment(), Some(FieldRequirment::HasOrigin(_))));
+    let nested = condition(json!({"All": [{"HasOrigin": "forest"}, {"Any": [{"HasOrigin": "hills"}, "QuestCompleted"]}]}));
+    assert_eq!(nested.required().len(), 1);
+    assert_eq!(nested.requirments().len(), 3);
+}
+
+#[test]
+fn conditions_explain_failures() {
+    let resources = HashMap::from([(GResource::Gold, 3)]);
+    let built = vec!["village_small".to_string()];
+    let facts = ConditionFacts { resources: &resources, turn: 2, heroes: 1, built: &built };
+
+    assert!(condition(json!({"Any": [{"HasOrigin": "hills"}, {"HasOrigin": "forest"}]})).is_met(&facts, &forest));
+    assert_eq!(
+        condition(json!({"Any": [{"HasOrigin": "hills"}, {"HasResource": {"resource": "Gold", "amount": 5}}]})).check(&facts, &forest),
+        Err("none of: origin is forest, not hills | 5 Gold needed, 3 owned".to_string())
+    );
+    assert_eq!(
+        condition(json!({"Not": {"HasOrigin": "forest"}})).check(&facts, &forest),
+        Err("HasOrigin forest is met".to_string())
+    );
+    assert!(condition(json!({"All": [{"TurnAtLeast": 2}, {"HeroCount": 1}, {"BuildingsBuilt": {"id": "village_small", "amount": 1}}]})).is_met(&facts, &forest));
+    assert!(condition(json!({"BuildingsBuilt": {"amount": 2}})).check(&facts, &forest).is_err());
+}
+
+#[test]
+fn buildings_use_conditions() {
+    let mut game = game_with(|id, data|if id == "cottage" {
+        data["requirments"].as_array_mut().unwrap().push(json!({"Any": [{"HasOrigin": "forest"}, {"HasOrigin": "hills"}]}));
+        data["requirments"].as_array_mut().unwrap().push(json!({"TurnAtLeast": 1}));
+    });
+    let cottages = |game: &STWGame|game.get_state().tiles.iter()
+        .filter(|e|game.get_possible_building_at_position(&e.position).iter().any(|b|b.id == "cottage"))
+        .map(|e|e.origin.to_string())
+        .collect::<Vec<_>>();
+    assert!(cottages(&game).is_empty());
+
+    game.perform_move(&GameMove::Wait).unwrap();
+    let origins = cottages(&game);
+    assert!(!origins.is_empty());
+    assert!(origins.iter().all(|e|e == "forest" || e == "hills"), "{:?}", origins);
+}
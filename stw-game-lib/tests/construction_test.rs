@@ -0,0 +1,68 @@
+mod common;
+use serde_json::json;
+use stw_game_lib::{game::{STWGame, game_move::GameMove, game_controller::GameCallback}, resource::enums::GResource};
+use crate::common::{game_with, gold, position_for};
+
+/// Cottages take two turns, without production to keep Gold simple.
+fn slow_cottage_game() -> STWGame {
+    game_with(|id, data|match id {
+        "cottage" => data["build_time"] = json!(2),
+        "village_small" => data["production"] = json!({}),
+        _ => {}
+    })
+}
+
+#[test]
+fn construction_finishes_after_build_time() {
+    let mut game = slow_cottage_game();
+    let pos = position_for(&game, "cottage");
+    let callbacks = game.perform_move(&GameMove::Build(pos, "cottage".to_string())).unwrap();
+    assert_eq!(callbacks, vec![
+        GameCallback::ChangedResource { resource: GResource::Gold, new_value: 3 },
+        GameCallback::ConstructionProgress { position: pos, field_type_id: "cottage".to_string(), turns_left: 2 },
+    ]);
+    let tile = game.get_tile_state(&pos).unwrap();
+    assert_eq!(tile.content, None);
+    assert_eq!(tile.construction, Some("cottage".to_string()));
+
+    let callbacks = game.perform_move(&GameMove::Wait).unwrap();
+    assert!(callbacks.contains(&GameCallback::ConstructionProgress { position: pos, field_type_id: "cottage".to_string(), turns_left: 1 }));
+    assert_eq!(game.get_constructions()[0].turns_left, 1);
+
+    let callbacks = game.perform_move(&GameMove::Wait).unwrap();
+    let finished = callbacks.iter().position(|e|*e == GameCallback::ConstructionFinished { position: pos, field_type_id: "cottage".to_string() });
+    let built = callbacks.iter().position(|e|*e == GameCallback::NewTileContent { position: pos, field_type_id: "cottage".to_string() });
+    assert!(finished.unwrap() < built.unwrap());
+    let tile = game.get_tile_state(&pos).unwrap();
+    assert_eq!(tile.content, Some("cottage".to_string()));
+    assert_eq!(tile.construction, None);
+    assert!(game.get_constructions().is_empty());
+}
+
+#[test]
+fn construction_blocks_the_tile() {
+    let mut game = slow_cottage_game();
+    let pos = position_for(&game, "cottage");
+    game.perform_move(&GameMove::Build(pos, "cottage".to_string())).unwrap();
+    assert!(game.get_possible_building_at_position(&pos).is_empty());
+    assert!(game.perform_move(&GameMove::Build(pos, "cottage".to_string())).is_err());
+    assert_eq!(game.get_map().get(&pos).unwrap().get_short_name(), "c~");
+}
+
+#[test]
+fn cancel_refunds_share_of_cost() {
+    let mut game = slow_cottage_game();
+    let pos = position_for(&game, "cottage");
+    assert!(game.perform_move(&GameMove::CancelConstruction(pos)).is_err());
+    game.perform_move(&GameMove::Build(pos, "cottage".to_string())).unwrap();
+    let callbacks = game.perform_move(&GameMove::CancelConstruction(pos)).unwrap();
+    // half of 2 Gold
+    assert_eq!(callbacks, vec![
+        GameCallback::ChangedResource { resource: GResource::Gold, new_value: 4 },
+        GameCallback::ConstructionCancelled { position: pos, field_type_id: "cottage".to_string() },
+    ]);
+    assert_eq!(gold(&game), 4);
+    assert!(game.get_constructions().is_empty());
+    assert_eq!(game.get_tile_state(&pos).unwrap().construction, None);
+    assert!(game.get_possible_building_at_position(&pos).iter().any(|e|e.id == "cottage"));
+}
@@ -0,0 +1,119 @@
+mod common;
+use serde_json::json;
+use stw_game_lib::{game::{STWGame, game_move::GameMove, game_controller::GameCallback}, resource::enums::GResource};
+use crate::common::{game_with, gold, position_for};
+
+fn rich_game() -> STWGame {
+    game_with(|id, data|match id {
+        "globals" => data["start_game_resources"] = json!({"Gold": 100, "Story": 10}),
+        "tavern" => data["build_time"] = json!(0),
+        "wilderness" => data["requirments"].as_array_mut().unwrap().push(json!("CantDemolish")),
+        _ => {}
+    })
+}
+
+#[test]
+fn demolish_clears_level_zero_building() {
+    let mut game = rich_game();
+    let pos = position_for(&game, "cottage");
+    game.perform_move(&GameMove::Build(pos, "cottage".to_string())).unwrap();
+
+    // a quarter of 2 Gold is nothing
+    let callbacks = game.perform_move(&GameMove::Demolish(pos)).unwrap();
+    assert_eq!(callbacks, vec![GameCallback::TileContentRemoved { position: pos }]);
+    assert_eq!(game.get_tile_state(&pos).unwrap().content, None);
+    assert!(game.perform_move(&GameMove::Demolish(pos)).is_err());
+}
+
+#[test]
+fn demolish_downgrades_and_refunds() {
+    let mut game = rich_game();
+    let pos = position_for(&game, "tavern_small");
+    game.perform_move(&GameMove::Build(pos, "tavern_small".to_string())).unwrap();
+    game.perform_move(&GameMove::Build(pos, "tavern".to_string())).unwrap();
+    let before = gold(&game);
+
+    let callbacks = game.perform_move(&GameMove::Demolish(pos)).unwrap();
+    // the first tavern gave a hero slot, and a quarter of 15 Gold
+    assert_eq!(callbacks, vec![
+        GameCallback::MaxHeroesDecreased { current_max_heroes: 1 },
+        GameCallback::ChangedResource { resource: GResource::Gold, new_value: before + 3 },
+        GameCallback::TileContentRemoved { position: pos },
+        GameCallback::BuildingDowngraded { position: pos, field_type_id: "tavern_small".to_string() },
+    ]);
+    assert_eq!(game.get_tile_state(&pos).unwrap().content, Some("tavern_small".to_string()));
+    // downgrades are not counted as buildings
+    assert_eq!(game.get_summary().buildings.len(), 2);
+
+    // then a quarter of the 7 Gold paid for the small tavern
+    game.perform_move(&GameMove::Demolish(pos)).unwrap();
+    assert_eq!(gold(&game), before + 3 + 1);
+    assert_eq!(game.get_tile_state(&pos).unwrap().content, None);
+}
+
+#[test]
+fn demolish_puts_back_the_upgraded_building() {
+    // a second level zero building on the tavern path, before tavern_small by id
+    let mut game = game_with(|id, data|match id {
+        "globals" => data["start_game_resources"] = json!({"Gold": 100, "Story": 10}),
+        "tavern" => data["build_time"] = json!(0),
+        "archers_hut" => {
+            data["build_path"] = json!("tavern");
+            data["path_level"] = json!(0);
+            data["requirments"] = json!([]);
+        },
+        _ => {}
+    });
+    let pos = position_for(&game, "tavern_small");
+    game.perform_move(&GameMove::Build(pos, "tavern_small".to_string())).unwrap();
+    game.perform_move(&GameMove::Build(pos, "tavern".to_string())).unwrap();
+
+    game.perform_move(&GameMove::Demolish(pos)).unwrap();
+    assert_eq!(game.get_tile_state(&pos).unwrap().content, Some("tavern_small".to_string()));
+}
+
+#[test]
+fn demolish_is_blocked() {
+    let mut game = rich_game();
+    let middle = game.get_state().map_middle;
+    assert!(game.perform_move(&GameMove::Demolish(middle)).is_err());
+
+    let pos = position_for(&game, "wilderness");
+    game.perform_move(&GameMove::Build(pos, "wilderness".to_string())).unwrap();
+    assert!(game.perform_move(&GameMove::Demolish(pos)).is_err());
+
+    for _ in 0..20 {
+        if !game.get_quests().is_empty() {
+            break;
+        }
+        game.perform_move(&GameMove::Wait).unwrap();
+    }
+    let quest = *game.get_quests().keys().next().unwrap();
+    assert!(game.perform_move(&GameMove::Demolish(quest)).is_err());
+    assert!(game.get_tile_state(&quest).unwrap().content.is_some());
+}
+
+#[test]
+fn demolish_undoes_instant_effects() {
+    let mut game = game_with(|id, data|match id {
+        "globals" => data["start_game_resources"] = json!({"Gold": 100}),
+        "cottage" => data["instant_effects"] = json!([
+            {"GiveResources": {"only_first_time": false, "resource": "Gold", "amount": 5}},
+            {"IncreaseMaxHeroes": {"only_first_time": false}}
+        ]),
+        _ => {}
+    });
+    let pos = position_for(&game, "cottage");
+    let start = gold(&game);
+    let max_heroes = game.get_state().max_heroes;
+
+    // building and demolishing again and again earns nothing
+    for _ in 0..3 {
+        game.perform_move(&GameMove::Build(pos, "cottage".to_string())).unwrap();
+        assert_eq!(game.get_state().max_heroes, max_heroes + 1);
+        let callbacks = game.perform_move(&GameMove::Demolish(pos)).unwrap();
+        assert!(callbacks.contains(&GameCallback::MaxHeroesDecreased { current_max_heroes: max_heroes }));
+        assert_eq!(game.get_state().max_heroes, max_heroes);
+    }
+    assert_eq!(gold(&game), start - 3 * 2);
+}
@@ -0,0 +1,54 @@
+mod common;
+use serde_json::json;
+use stw_game_lib::{game::{map::TilePos, game_move::GameMove, game_controller::GameCallback}, resource::enums::GResource};
+use crate::common::{game_with, gold};
+
+#[test]
+fn production_and_upkeep_are_paid_every_turn() {
+    let mut game = game_with(|id, data|if id == "village_small" {
+        data["production"] = json!({"Story": 2});
+        data["upkeep"] = json!({"Gold": 1});
+    });
+    let before = gold(&game);
+    let callbacks = game.perform_move(&GameMove::Wait).unwrap();
+    assert!(callbacks.contains(&GameCallback::ChangedResource { resource: GResource::Gold, new_value: before - 1 }));
+    assert!(callbacks.contains(&GameCallback::ChangedResource { resource: GResource::Story, new_value: 2 }));
+    assert!(game.get_tile_state(&game.get_state().map_middle).unwrap().active);
+}
+
+#[test]
+fn unpaid_buildings_go_inactive() {
+    let mut game = game_with(|id, data|if id == "village_small" {
+        data["production"] = json!({"Gold": 1});
+        data["upkeep"] = json!({"Story": 1});
+    });
+    let middle = game.get_state().map_middle;
+    let before = gold(&game);
+    let callbacks = game.perform_move(&GameMove::Wait).unwrap();
+    assert!(callbacks.contains(&GameCallback::BuildingActiveChanged { position: middle, active: false }));
+    assert_eq!(gold(&game), before);
+    assert!(!game.get_tile_state(&middle).unwrap().active);
+}
+
+#[test]
+fn production_depends_on_origin() {
+    let mut game = game_with(|id, data|if id == "cottage" {
+        data["conditional_production"] = json!([{"resource": "Gold", "amount": 1, "requirments": [{"HasOrigin": "meadow"}]}]);
+    });
+    let cottages: Vec<TilePos> = game.get_state().tiles.iter()
+        .filter(|e|game.get_possible_building_at_position(&e.position).iter().any(|b|b.id == "cottage"))
+        .map(|e|e.position)
+        .collect();
+    let meadow = cottages.iter().find(|e|game.get_tile_state(e).unwrap().origin == "meadow").copied();
+    let other = cottages.iter().find(|e|game.get_tile_state(e).unwrap().origin != "meadow").copied();
+
+    game.perform_move(&GameMove::Build(other.unwrap(), "cottage".to_string())).unwrap();
+    let before = gold(&game);
+    assert!(game.perform_move(&GameMove::Wait).unwrap().iter().all(|e|!matches!(e, GameCallback::ChangedResource { .. })));
+    assert_eq!(gold(&game), before);
+
+    game.perform_move(&GameMove::Build(meadow.unwrap(), "cottage".to_string())).unwrap();
+    let before = gold(&game);
+    let callbacks = game.perform_move(&GameMove::Wait).unwrap();
+    assert!(callbacks.contains(&GameCallback::ChangedResource { resource: GResource::Gold, new_value: before + 1 }));
+}
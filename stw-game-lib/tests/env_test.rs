@@ -0,0 +1,69 @@
+mod common;
+use stw_game_lib::{env::{STWEnv, EnvConfig, RewardConfig, TILE_FEATURES}, game::{map::TilePos, game_move::GameMove}, resource::enums::GResource};
+use crate::common::load_resources;
+
+fn prepare_env(max_turns: u32) -> STWEnv {
+    let config = EnvConfig::new()
+        .set_resources(load_resources())
+        .set_map_size(37)
+        .set_max_turns(max_turns);
+    STWEnv::new(config, "env seed").unwrap()
+}
+
+#[test]
+fn observation_has_fixed_shape() {
+    let mut env = prepare_env(100);
+    let observation = env.observe();
+    assert_eq!(observation.tiles.len(), env.tile_count() * TILE_FEATURES);
+    assert_eq!(observation.globals.len(), env.globals_len());
+    assert_eq!(observation.action_mask.len(), env.action_count());
+
+    let wait = env.encode_action(&GameMove::Wait).unwrap();
+    let observation = env.step(wait).unwrap().observation;
+    assert_eq!(observation.tiles.len(), env.tile_count() * TILE_FEATURES);
+    assert_eq!(observation.globals.len(), env.globals_len());
+}
+
+#[test]
+fn action_mask_matches_legal_moves() {
+    let env = prepare_env(100);
+    let legal = env.get_game().get_possible_moves();
+    let mask = env.action_mask();
+    assert_eq!(mask.iter().filter(|e|**e).count(), legal.len());
+    for game_move in legal {
+        let index = env.encode_action(&game_move).unwrap();
+        assert!(mask[index]);
+        assert_eq!(env.decode_action(index), Some(game_move));
+    }
+    assert_eq!(env.decode_action(env.action_count()), None);
+}
+
+#[test]
+fn step_scores_with_reward_config() {
+    let mut env = prepare_env(100);
+    let build = env.encode_action(&GameMove::Build(TilePos { q: 8, r: 18 }, "cottage".to_string())).unwrap();
+    let gold = env.get_game().get_resources()[&GResource::Gold] as f32;
+
+    let res = env.step(build).unwrap();
+    let spent = gold - env.get_game().get_resources()[&GResource::Gold] as f32;
+    let reward = RewardConfig::default();
+    assert_eq!(res.reward, reward.new_building - reward.resources[&GResource::Gold] * spent);
+    assert!(!res.done);
+    assert!(!res.callbacks.is_empty());
+
+    let res = env.step(build).unwrap();
+    assert_eq!(res.reward, reward.illegal_action);
+    assert!(res.callbacks.is_empty());
+    assert!(env.step(env.action_count()).is_err());
+}
+
+#[test]
+fn episode_ends_after_max_turns() {
+    let mut env = prepare_env(2);
+    let wait = env.encode_action(&GameMove::Wait).unwrap();
+    assert!(!env.step(wait).unwrap().done);
+    assert!(env.step(wait).unwrap().done);
+
+    let observation = env.reset("env seed").unwrap();
+    assert_eq!(observation, prepare_env(2).observe());
+}
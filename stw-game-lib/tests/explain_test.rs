@@ -0,0 +1,62 @@
+mod common;
+use std::collections::BTreeMap;
+
+use serde_json::json;
+use stw_game_lib::{game::explain::Verdict, resource::enums::GResource};
+use crate::common::game_with;
+
+const BUILDINGS: [&str; 11] = ["village_small", "village", "tavern_small", "tavern", "cottage", "archers_hut", "forge_small", "alchemists_house", "wilderness", "bandit_camp_small", "caves"];
+
+#[test]
+fn explanation_names_violating_building() {
+    let game = game_with(|_, _|{});
+    let mid = game.get_map().get_mid_position();
+    let pos = mid.positions_in_distance(3)[0];
+    let explanation = game.explain_building(&pos, "village_small").unwrap();
+
+    assert!(!explanation.buildable);
+    assert!(explanation.placement.met);
+    assert_eq!(explanation.requirments[1], Verdict {
+        requirment: "NoNearFieldWithPath town within 5".to_string(),
+        met: false,
+        reason: Some(format!("violated by village_small at ({},{})", mid.q, mid.r))
+    });
+    assert_eq!(explanation.shortfalls, BTreeMap::from([(GResource::Gold, 5), (GResource::RareTrophy, 1)]));
+}
+
+#[test]
+fn explanation_covers_placement() {
+    let game = game_with(|_, _|{});
+    let mid = game.get_map().get_mid_position();
+
+    let upgrade = game.explain_building(&mid.positions_in_distance(3)[0], "village").unwrap();
+    assert_eq!(upgrade.placement.reason, Some("needs level 0 of town to upgrade".to_string()));
+    assert!(upgrade.requirments.is_empty());
+
+    let replacement = game.explain_building(&mid, "cottage").unwrap();
+    assert_eq!(replacement.placement.requirment, "BuildOn village_small");
+    assert!(!replacement.placement.met);
+}
+
+#[test]
+fn explanation_agrees_with_possible_buildings() {
+    let game = game_with(|id, data|if id == "globals" {
+        data["start_game_resources"] = json!({"Gold": 12, "Story": 1});
+    });
+    for tile in game.get_state().tiles.iter() {
+        let possible = game.get_possible_building_at_position(&tile.position);
+        for id in BUILDINGS {
+            let explanation = game.explain_building(&tile.position, id).unwrap();
+            let expected = possible.iter().any(|e|e.id == id && !e.not_enought_resources);
+            assert_eq!(explanation.buildable, expected, "{} at {:?}: {:?}", id, tile.position, explanation);
+        }
+    }
+}
+
+#[test]
+fn explanation_of_unknown_building_or_tile_fails() {
+    let game = game_with(|_, _|{});
+    let mid = game.get_map().get_mid_position();
+    assert!(game.explain_building(&mid, "castle").is_err());
+    assert!(game.explain_building(&mid.moved(1000, 0), "cottage").is_err());
+}
@@ -102,4 +102,11 @@ fn game_test() {
     // decision
 
 
-}
\ No newline at end of file
+}
+
+#[test]
+fn game_needs_map() {
+    let config = || stw_game_lib::GameConfig::new().set_resources(load_resources()).set_seed("test seed");
+    assert!(config().set_map_size(0).start_game().is_err());
+    assert!(config().set_map_size(1).start_game().is_ok());
+}
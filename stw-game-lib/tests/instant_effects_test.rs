@@ -0,0 +1,66 @@
+mod common;
+use serde_json::{json, Value};
+use stw_game_lib::{game::{STWGame, game_move::GameMove, game_controller::GameCallback}, resource::enums::{GResource, HeroSkill}};
+use crate::common::{game_with, position_for};
+
+/// Game where cottages play `effects`.
+fn effects_game(effects: Value) -> STWGame {
+    game_with(|id, data|match id {
+        "globals" => data["start_game_resources"] = json!({"Gold": 100, "Story": 10}),
+        "cottage" => data["instant_effects"] = effects.clone(),
+        _ => {}
+    })
+}
+
+#[test]
+fn show_fields_discovers_closest_tiles() {
+    let mut game = game_with(|id, data|match id {
+        "globals" => data["map_visible_on_start"] = json!(1),
+        "cottage" => data["instant_effects"] = json!([{"ShowFields": {"only_first_time": false, "amount": 18}}]),
+        _ => {}
+    });
+    let pos = position_for(&game, "cottage");
+    let callbacks = game.perform_move(&GameMove::Build(pos, "cottage".to_string())).unwrap();
+
+    assert!(callbacks.iter().any(|e|matches!(e, GameCallback::TileDiscovered { .. })));
+    for tile in game.get_state().tiles.iter().filter(|e|e.position.distance(&pos) <= 2) {
+        assert!(tile.visible, "{:?}", tile.position);
+    }
+}
+
+#[test]
+fn increase_skill_levels_every_hero() {
+    let mut game = effects_game(json!([{"IncreaseSkill": {"only_first_time": false, "skill": "Charisma", "amount": 1.0}}]));
+    game.perform_move(&GameMove::Wait).unwrap();
+    let charisma = *game.get_heroes()[0].get_skills().get(&HeroSkill::Charisma).unwrap_or(&0.0);
+
+    let pos = position_for(&game, "cottage");
+    let callbacks = game.perform_move(&GameMove::Build(pos, "cottage".to_string())).unwrap();
+    assert!(callbacks.contains(&GameCallback::HeroLeveled { hero_number: 0, skill: HeroSkill::Charisma, new_skill_value: charisma + 1.0 }));
+    assert_eq!(game.get_heroes()[0].get_skills()[&HeroSkill::Charisma], charisma + 1.0);
+}
+
+#[test]
+fn first_time_effects_play_once() {
+    let mut game = effects_game(json!([{"GiveResources": {"only_first_time": true, "resource": "Gold", "amount": 5}}]));
+    let pos = position_for(&game, "cottage");
+    game.perform_move(&GameMove::Build(pos, "cottage".to_string())).unwrap();
+    let gold = game.get_resources()[&GResource::Gold];
+    assert_eq!(gold, 100 - 2 + 5);
+
+    let pos = position_for(&game, "cottage");
+    game.perform_move(&GameMove::Build(pos, "cottage".to_string())).unwrap();
+    assert_eq!(game.get_resources()[&GResource::Gold], gold - 2);
+}
+
+#[test]
+fn learn_about_action_is_rejected() {
+    // same map, where the cottage could be built without the effect
+    let pos = position_for(&effects_game(json!([])), "cottage");
+    let mut game = effects_game(json!([{"LearnAboutAction": {"only_first_time": false}}]));
+    assert!(!game.get_possible_building_at_position(&pos).iter().any(|e|e.id == "cottage"));
+
+    let error = game.perform_move(&GameMove::Build(pos, "cottage".to_string())).err().unwrap();
+    assert!(error.msg.contains("LearnAboutAction"), "{}", error.msg);
+    assert!(!game.explain_building(&pos, "cottage").unwrap().effects.met);
+}
@@ -0,0 +1,52 @@
+mod common;
+use serde_json::json;
+use stw_game_lib::{game::{STWGame, game_move::GameMove, game_controller::GameCallback}, resource::enums::GResource};
+use crate::common::game_with;
+
+fn rich_game() -> STWGame {
+    game_with(|id, data|if id == "globals" {
+        data["start_game_resources"] = json!({"Gold": 20});
+    })
+}
+
+fn trade(give: GResource, take: GResource, amount: u32) -> GameMove {
+    GameMove::Trade { give, take, amount }
+}
+
+fn rate(game: &STWGame, id: &str) -> f32 {
+    game.get_market().into_iter().find(|e|e.id == id).unwrap().rate
+}
+
+#[test]
+fn trade_changes_resources() {
+    let mut game = rich_game();
+    assert_eq!(game.get_trade_cost(GResource::Gold, GResource::GreenTrophy, 2), Some(8));
+    let callbacks = game.perform_move(&trade(GResource::Gold, GResource::GreenTrophy, 2)).unwrap();
+    assert_eq!(callbacks, vec![
+        GameCallback::ChangedResource { resource: GResource::Gold, new_value: 12 },
+        GameCallback::ChangedResource { resource: GResource::GreenTrophy, new_value: 2 },
+    ]);
+}
+
+#[test]
+fn demand_raises_rate_until_it_recovers() {
+    let mut game = rich_game();
+    let base = rate(&game, "gold_for_green_trophy");
+    game.perform_move(&trade(GResource::Gold, GResource::GreenTrophy, 1)).unwrap();
+    let raised = rate(&game, "gold_for_green_trophy");
+    assert!(raised > base);
+    game.perform_move(&GameMove::Wait).unwrap();
+    let recovered = rate(&game, "gold_for_green_trophy");
+    assert!(recovered < raised && recovered > base);
+}
+
+#[test]
+fn invalid_trades_are_rejected() {
+    let mut game = rich_game();
+    // no tavern yet
+    assert!(!game.get_market().into_iter().find(|e|e.id == "white_trophy_for_gold").unwrap().open);
+    assert!(game.perform_move(&trade(GResource::WhiteTrophy, GResource::Gold, 1)).is_err());
+    assert!(game.perform_move(&trade(GResource::Gold, GResource::GreenTrophy, 0)).is_err());
+    assert!(game.perform_move(&trade(GResource::Gold, GResource::GreenTrophy, 10)).is_err());
+    assert_eq!(game.get_resources()[&GResource::Gold], 20);
+}
@@ -0,0 +1,60 @@
+mod common;
+use serde_json::json;
+use stw_game_lib::{game::{STWGame, map::TilePos, game_move::GameMove, game_controller::GameCallback, objectives::GameOutcome}, resource::enums::{GResource, LossCondition, WinCondition}, GameConfig};
+use crate::common::load_resources;
+
+fn game_with_objectives(objectives: serde_json::Value, scoring: serde_json::Value) -> STWGame {
+    let mut resources = load_resources();
+    for resource in resources.iter_mut().filter(|e|e.0 == "Globals") {
+        resource.1["data"]["objectives"] = objectives.clone();
+        resource.1["data"]["scoring"] = scoring.clone();
+    }
+    let mut game = GameConfig::new()
+        .set_map_size(37)
+        .set_resources(resources)
+        .set_seed("test seed")
+        .start_game()
+        .unwrap();
+    game.perform_move(&GameMove::Build(TilePos { q: 8, r: 18 }, "cottage".to_string())).unwrap();
+    game
+}
+
+#[test]
+fn game_is_won_and_rejects_moves() {
+    let mut game = game_with_objectives(json!({
+        "win": [{"SurviveTurns": 2}]
+    }), json!({
+        "resources": {"Gold": 2},
+        "win": 100
+    }));
+    assert!(!game.perform_move(&GameMove::Wait).unwrap().iter().any(|e|matches!(e, GameCallback::GameWon { .. })));
+    let callbacks = game.perform_move(&GameMove::Wait).unwrap();
+
+    let score = game.get_resources()[&GResource::Gold] * 2 + 100;
+    assert_eq!(callbacks.last(), Some(&GameCallback::GameWon { condition: WinCondition::SurviveTurns(2), score }));
+    assert_eq!(game.get_outcome(), Some(&GameOutcome::Won { condition: WinCondition::SurviveTurns(2), score }));
+    assert_eq!(game.get_state().outcome, game.get_outcome().cloned());
+    assert!(game.get_possible_moves().is_empty());
+    assert!(game.perform_move(&GameMove::Wait).is_err());
+    assert!(game.perform_move(&GameMove::RenameHero(0, "Bob".to_string())).is_err());
+}
+
+#[test]
+fn loss_is_checked_before_win() {
+    let mut game = game_with_objectives(json!({
+        "win": [{"SurviveTurns": 1}],
+        "loss": [{"TurnLimit": 0}]
+    }), json!({}));
+    let callbacks = game.perform_move(&GameMove::Wait).unwrap();
+    assert_eq!(callbacks.last(), Some(&GameCallback::GameLost { condition: LossCondition::TurnLimit(0), score: 0 }));
+    assert!(game.is_game_over());
+}
+
+#[test]
+fn game_without_objectives_goes_on() {
+    let mut game = game_with_objectives(json!({}), json!({}));
+    for _ in 0..20 {
+        game.perform_move(&GameMove::Wait).unwrap();
+    }
+    assert_eq!(game.get_outcome(), None);
+}
@@ -0,0 +1,66 @@
+mod common;
+use stw_game_lib::{game::{STWGame, map::TilePos, game_move::GameMove}, GameConfig};
+use crate::common::load_resources;
+
+fn game_before_history() -> STWGame {
+    let mut game = GameConfig::new()
+        .set_map_size(37)
+        .set_resources(load_resources())
+        .set_seed("test seed")
+        .start_game()
+        .unwrap();
+    for game_move in [
+        GameMove::Build(TilePos { q: 8, r: 18 }, "cottage".to_string()),
+        GameMove::Wait,
+        GameMove::Wait
+    ] {
+        game.perform_move(&game_move).unwrap();
+    }
+    game
+}
+
+#[test]
+fn planned_routes_are_playable_and_sorted() {
+    let quest_pos = TilePos { q: 8, r: 18 };
+    let mut game = game_before_history();
+    let decisions: Vec<String> = game.get_quests()[&quest_pos].get_quest_type().data.quest_decisions.iter()
+        .map(|e|e.id.to_string())
+        .collect();
+    let before = game.plan_routes(&quest_pos, 0, &decisions[0], 5).unwrap();
+    assert!(game.plan_routes(&quest_pos, 0, "no such decision", 5).is_err());
+
+    game.perform_move(&GameMove::StartHistory(quest_pos, 0)).unwrap();
+    for decision in &decisions {
+        let routes = game.plan_routes(&quest_pos, 0, decision, 5).unwrap();
+        assert!(!routes.is_empty() && routes.len() <= 5);
+        for pair in routes.windows(2) {
+            assert!(pair[0].decision.success_probability >= pair[1].decision.success_probability);
+        }
+        for route in &routes {
+            assert_eq!(game.decision_success_probability(decision, &route.moves).unwrap(), route.decision);
+        }
+    }
+    // planning before the history starts gives the same routes
+    assert_eq!(game.plan_routes(&quest_pos, 0, &decisions[0], 5).unwrap(), before);
+
+    let route = game.best_route(&decisions[0]).unwrap();
+    for (pos, action) in route.moves {
+        game.perform_move(&GameMove::PlayMove(pos, action)).unwrap();
+    }
+}
+
+#[test]
+fn heroes_are_ranked_by_their_best_route() {
+    let quest_pos = TilePos { q: 8, r: 18 };
+    let game = game_before_history();
+    let ranking = game.rank_heroes_for_quest(&quest_pos).unwrap();
+    assert_eq!(ranking.len(), game.get_heroes().len());
+    for pair in ranking.windows(2) {
+        assert!(pair[0].route.decision.success_probability >= pair[1].route.decision.success_probability);
+    }
+    for recommendation in &ranking {
+        let best = game.plan_routes(&quest_pos, recommendation.hero_index, &recommendation.decision, 1).unwrap();
+        assert_eq!(best, vec![recommendation.route.clone()]);
+    }
+    assert!(game.rank_heroes_for_quest(&TilePos { q: 0, r: 0 }).is_err());
+}
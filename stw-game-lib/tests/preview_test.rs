@@ -0,0 +1,85 @@
+mod common;
+use stw_game_lib::{game::{STWGame, map::TilePos, game_move::GameMove, game_controller::GameCallback}, resource::enums::HeroSkill, GameConfig};
+use crate::common::load_resources;
+
+/// Hero 0 starts the quest next to the village, as in `game_test`.
+fn game_in_history() -> STWGame {
+    let mut game = GameConfig::new()
+        .set_map_size(37)
+        .set_resources(load_resources())
+        .set_seed("test seed")
+        .start_game()
+        .unwrap();
+    for game_move in [
+        GameMove::Build(TilePos { q: 8, r: 18 }, "cottage".to_string()),
+        GameMove::Wait,
+        GameMove::Wait,
+        GameMove::StartHistory(TilePos { q: 8, r: 18 }, 0)
+    ] {
+        game.perform_move(&game_move).unwrap();
+    }
+    game
+}
+
+fn path() -> Vec<(TilePos, String)> {
+    vec![
+        (TilePos { q: 9, r: 18 }, "shopping_village".to_string()),
+        (TilePos { q: 9, r: 17 }, "gathering_meadow".to_string()),
+        (TilePos { q: 8, r: 17 }, "gathering_meadow".to_string())
+    ]
+}
+
+#[test]
+fn preview_action_matches_the_roll() {
+    let mut game = game_in_history();
+    let mut other = game_in_history();
+    let (pos, action) = path()[0].clone();
+
+    let preview = game.preview_action(&pos, &action).unwrap();
+    assert_eq!(preview.points.keys().copied().collect::<Vec<_>>(), vec![HeroSkill::Alechemy, HeroSkill::Magic, HeroSkill::MleeFight, HeroSkill::DistanceFight]);
+    for range in preview.points.values() {
+        assert!(range.min <= range.expected && range.expected <= range.max);
+        assert!((range.expected - (range.min + range.max) / 2.0).abs() < 1e-6);
+    }
+    let modificators = &game.get_history().unwrap().current_modificators;
+    assert_eq!(preview.modificators[&HeroSkill::Magic], (modificators[&HeroSkill::Magic], modificators[&HeroSkill::Magic] + 0.2));
+    assert_eq!(preview.eternal_probability, 0.2);
+
+    // previews do not use the game rng
+    let callbacks = game.perform_move(&GameMove::PlayMove(pos, action.to_string())).unwrap();
+    assert_eq!(callbacks, other.perform_move(&GameMove::PlayMove(pos, action)).unwrap());
+    let Some(GameCallback::HeroMoved { success, .. }) = callbacks.first() else { panic!("{:?}", callbacks) };
+    for (skill, range) in preview.points {
+        let got = game.get_history().unwrap().points_got[&skill];
+        assert!((got - (range.min + success * (range.max - range.min))).abs() < 1e-5);
+    }
+
+    assert!(game.preview_action(&TilePos { q: 4, r: 18 }, "gathering_meadow").is_err());
+}
+
+#[test]
+fn decision_probability_over_a_path() {
+    let game = game_in_history();
+
+    let now = game.decision_success_probability("help_with_gathering", &[]).unwrap();
+    assert!(now.exact && now.permitted);
+    assert_eq!((now.points.min, now.points.max, now.success_probability), (0.0, 0.0, 0.0));
+
+    let blocked = game.decision_success_probability("give_resources", &path()[..1]).unwrap();
+    assert!(!blocked.permitted);
+    assert_eq!(blocked.success_probability, 0.0);
+
+    let one_step = game.decision_success_probability("help_with_gathering", &[(TilePos { q: 9, r: 18 }, "collect_info_village".to_string())]).unwrap();
+    assert!(one_step.exact);
+    assert!(one_step.points.min > 0.0 && one_step.points.min < one_step.points.max);
+    let chance = |points: f32|points / (points + 3.0);
+    assert!(chance(one_step.points.min) < one_step.success_probability && one_step.success_probability < chance(one_step.points.max));
+
+    let full = game.decision_success_probability("give_resources", &path()).unwrap();
+    assert!(full.permitted && !full.exact);
+    assert!(chance(full.points.min) < full.success_probability && full.success_probability < chance(full.points.max));
+    assert_eq!(full, game.decision_success_probability("give_resources", &path()).unwrap());
+
+    assert!(game.decision_success_probability("flee", &[]).is_err());
+    assert!(game.decision_success_probability("give_resources", &path()[1..]).is_err());
+}
@@ -0,0 +1,33 @@
+use serde_json::json;
+use stw_game_lib::{game::{map::TilePos, game_move::{GameMove, PossibleBuilding}, game_controller::GameCallback, BadMove}, resource::enums::GResource};
+
+#[test]
+fn game_move_json() {
+    let build = GameMove::Build(TilePos { q: 8, r: 18 }, "cottage".to_string());
+    assert_eq!(serde_json::to_value(&build).unwrap(), json!({"type": "Build", "data": [{"q": 8, "r": 18}, "cottage"]}));
+    assert_eq!(serde_json::to_value(GameMove::Wait).unwrap(), json!({"type": "Wait"}));
+    assert_eq!(serde_json::to_value(GameMove::MakeDecision("flee".to_string())).unwrap(), json!({"type": "MakeDecision", "data": "flee"}));
+
+    let parsed: GameMove = serde_json::from_value(json!({"type": "StartHistory", "data": [{"q": 8, "r": 18}, 0]})).unwrap();
+    assert_eq!(parsed, GameMove::StartHistory(TilePos { q: 8, r: 18 }, 0));
+    assert!(serde_json::from_value::<GameMove>(json!({"type": "Fly"})).is_err());
+}
+
+#[test]
+fn game_callback_json() {
+    let callback = GameCallback::ChangedResource { resource: GResource::Gold, new_value: 3 };
+    let value = serde_json::to_value(&callback).unwrap();
+    assert_eq!(value, json!({"type": "ChangedResource", "data": {"resource": "Gold", "new_value": 3}}));
+    assert_eq!(serde_json::from_value::<GameCallback>(value).unwrap(), callback);
+
+    let callback = GameCallback::NewTileContent { position: TilePos { q: 8, r: 18 }, field_type_id: "cottage".to_string() };
+    let text = serde_json::to_string(&callback).unwrap();
+    assert_eq!(serde_json::from_str::<GameCallback>(&text).unwrap(), callback);
+}
+
+#[test]
+fn possible_building_and_bad_move_json() {
+    let building = PossibleBuilding { id: "village".to_string(), not_enought_resources: true };
+    assert_eq!(serde_json::to_value(&building).unwrap(), json!({"id": "village", "not_enought_resources": true}));
+    assert_eq!(serde_json::to_value(BadMove::new("nope".to_string())).unwrap(), json!({"msg": "nope"}));
+}
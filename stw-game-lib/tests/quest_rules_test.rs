@@ -0,0 +1,121 @@
+mod common;
+use serde_json::{json, Value};
+use stw_game_lib::{game::{STWGame, map::TilePos, game_move::GameMove, game_controller::GameCallback}, resource::{enums::GResource, resource_data::QuestDecision}};
+use crate::common::game_with;
+
+const QUEST_POS: TilePos = TilePos { q: 8, r: 18 };
+
+/// Game where the `hungry_villagers` quest at `QUEST_POS` has `decision`
+/// and `penalty`, played up to the decision the same way as in `game_test`.
+fn game_at_decision(decision: Value, penalty: Value) -> STWGame {
+    let mut game = game_with(|id, data|if id == "hungry_villagers" {
+        data["quest_decisions"] = json!([decision]);
+        data["penalty"] = penalty.clone();
+    });
+    game.perform_move(&GameMove::Build(QUEST_POS, "cottage".to_string())).unwrap();
+    game.perform_move(&GameMove::Wait).unwrap();
+    game.perform_move(&GameMove::Wait).unwrap();
+    game.perform_move(&GameMove::StartHistory(QUEST_POS, 0)).unwrap();
+    for (pos, action) in [((9, 18), "shopping_village"), ((9, 17), "gathering_meadow"), ((8, 17), "gathering_meadow")] {
+        game.perform_move(&GameMove::PlayMove(TilePos { q: pos.0, r: pos.1 }, action.to_string())).unwrap();
+    }
+    game
+}
+
+fn decision(min_required_points: f32, treasure: Value) -> Value {
+    json!({
+        "id": "decide",
+        "required_skills": {"Charisma": 1.0},
+        "min_required_points": min_required_points,
+        "points_50p": 0.000001,
+        "additional_points": [{"DidActionFamily": "gathering"}],
+        "required": [],
+        "treasure": treasure
+    })
+}
+
+#[test]
+fn success_probability_grows_with_points() {
+    let decision: QuestDecision = serde_json::from_value(json!({
+        "id": "decide",
+        "required_skills": {},
+        "min_required_points": 1.0,
+        "points_50p": 3.0,
+        "additional_points": [],
+        "required": [],
+        "treasure": {}
+    })).unwrap();
+
+    assert_eq!(decision.success_probability(0.5), 0.0);
+    assert_eq!(decision.success_probability(3.0), 0.5);
+    assert!(decision.success_probability(9.0) > decision.success_probability(3.0));
+    assert!(decision.success_probability(9.0) < 1.0);
+}
+
+#[test]
+fn successful_decision_gives_treasure() {
+    let mut game = game_at_decision(decision(0.0, json!({"Gold": [5.0, 5.0]})), json!([]));
+    let gold = game.get_resources()[&GResource::Gold];
+
+    let callbacks = game.perform_move(&GameMove::MakeDecision("decide".to_string())).unwrap();
+    assert_eq!(callbacks[0], GameCallback::QuestFinished { quest_pos: QUEST_POS, quest_id: "hungry_villagers".to_string(), decision: "decide".to_string(), success: true });
+    assert_eq!(game.get_resources()[&GResource::Gold], gold + 5);
+    assert!(game.get_state().tiles.iter().any(|e|e.position == QUEST_POS && e.quest_completed));
+    assert!(game.get_quests().is_empty());
+}
+
+#[test]
+fn failed_decision_plays_penalties() {
+    let mut game = game_at_decision(
+        decision(1000.0, json!({"Gold": [5.0, 5.0]})),
+        json!(["DestroyQuestTile", {"LooseResources": {"resource": "Gold", "amount": 1000}}, {"KillHero": 1.0}])
+    );
+
+    let callbacks = game.perform_move(&GameMove::MakeDecision("decide".to_string())).unwrap();
+    assert!(matches!(&callbacks[0], GameCallback::QuestFinished { success: false, .. }));
+    assert!(callbacks.contains(&GameCallback::TileContentRemoved { position: QUEST_POS }));
+    assert!(callbacks.contains(&GameCallback::HeroDied { hero_number: 0 }));
+    assert_eq!(game.get_resources()[&GResource::Gold], 0);
+    assert!(game.get_heroes().is_empty());
+    assert!(game.get_state().tiles.iter().any(|e|e.position == QUEST_POS && e.content.is_none()));
+}
+
+#[test]
+fn decision_is_rejected_outside_history() {
+    let mut game = game_with(|_, _|{});
+    assert!(game.perform_move(&GameMove::MakeDecision("give_resources".to_string())).is_err());
+}
+
+#[test]
+fn rename_hero_checks_name_and_index() {
+    let mut game = game_with(|_, _|{});
+    game.perform_move(&GameMove::Wait).unwrap();
+    assert_eq!(
+        game.perform_move(&GameMove::RenameHero(0, "Jan".to_string())).unwrap(),
+        vec![GameCallback::HeroRenamed { hero_number: 0, name: "Jan".to_string() }]
+    );
+    assert_eq!(game.get_heroes()[0].get_name(), "Jan");
+    assert!(game.perform_move(&GameMove::RenameHero(0, " ".to_string())).is_err());
+    assert!(game.perform_move(&GameMove::RenameHero(5, "Ola".to_string())).is_err());
+}
+
+#[test]
+fn empty_move_walks_the_hero() {
+    let mut game = game_with(|id, data|if id == "meadow" {
+        data["possible_actions"] = json!([]);
+    });
+    game.perform_move(&GameMove::Build(QUEST_POS, "cottage".to_string())).unwrap();
+    game.perform_move(&GameMove::Wait).unwrap();
+    game.perform_move(&GameMove::Wait).unwrap();
+    game.perform_move(&GameMove::StartHistory(QUEST_POS, 0)).unwrap();
+    game.perform_move(&GameMove::PlayMove(TilePos { q: 9, r: 18 }, "shopping_village".to_string())).unwrap();
+    let path_left = game.get_state().history.unwrap().path_left;
+
+    let meadow = TilePos { q: 9, r: 17 };
+    assert!(game.get_possible_hero_moves().contains(&(meadow, String::new())));
+    game.perform_move(&GameMove::PlayMove(meadow, String::new())).unwrap();
+
+    let history = game.get_state().history.unwrap();
+    assert_eq!(history.current_pos, Some(meadow));
+    assert_eq!(history.path_left, path_left - 1);
+}
@@ -0,0 +1,146 @@
+mod common;
+use serde_json::{json, Value};
+use stw_game_lib::{game::{STWGame, map::TilePos, game_move::GameMove, game_controller::GameCallback}, resource::enums::GResource, GameConfig};
+use crate::common::{game_with, load_resources, position_for};
+
+/// Game where the bandit camp can replace with `rule` and `replacement`.
+fn camp_game(rule: Value, replacement: Value) -> STWGame {
+    game_with(|id, data|match id {
+        "globals" => data["start_game_resources"] = json!({"Gold": 100, "Story": 10}),
+        "wilderness" => data["instant_effects"] = json!([
+            {"GiveResources": {"only_first_time": false, "resource": "Gold", "amount": 5}},
+            {"IncreaseMaxHeroes": {"only_first_time": false}}
+        ]),
+        "bandit_camp_small" => {
+            data["requirments"].as_array_mut().unwrap().push(rule.clone());
+            data["replacement"] = replacement.clone();
+        },
+        _ => {}
+    })
+}
+
+fn wilderness(game: &mut STWGame) -> TilePos {
+    let pos = position_for(game, "wilderness");
+    game.perform_move(&GameMove::Build(pos, "wilderness".to_string())).unwrap();
+    pos
+}
+
+#[test]
+fn camp_replaces_wilderness_by_id_path_or_characteristic() {
+    for rule in [json!({"Replaces": "wilderness"}), json!({"ReplacesPath": "forest"}), json!({"ReplacesCharacteristic": "Mysterious"})] {
+        let mut game = camp_game(rule.clone(), json!({}));
+        let pos = wilderness(&mut game);
+        assert!(game.get_possible_building_at_position(&pos).iter().any(|e|e.id == "bandit_camp_small"), "{}", rule);
+
+        game.perform_move(&GameMove::Build(pos, "bandit_camp_small".to_string())).unwrap();
+        assert_eq!(game.get_tile_state(&pos).unwrap().content, Some("bandit_camp_small".to_string()));
+        assert_eq!(game.get_resources()[&GResource::Story], 8);
+    }
+}
+
+#[test]
+fn camp_does_not_replace_other_content() {
+    let mut game = camp_game(json!({"ReplacesPath": "town"}), json!({}));
+    let pos = wilderness(&mut game);
+    assert!(!game.get_possible_building_at_position(&pos).iter().any(|e|e.id == "bandit_camp_small"));
+    assert_eq!(
+        game.explain_building(&pos, "bandit_camp_small").unwrap().placement.reason,
+        Some("can not be built on wilderness".to_string())
+    );
+    assert!(game.perform_move(&GameMove::Build(pos, "bandit_camp_small".to_string())).is_err());
+}
+
+#[test]
+fn replacement_discounts_cost_and_undoes_effects() {
+    let mut game = camp_game(json!({"ReplacesPath": "forest"}), json!({"undo_effects": true, "cost_discount": 1.0}));
+    let pos = wilderness(&mut game);
+    assert_eq!(game.get_resources()[&GResource::Gold], 105);
+    assert_eq!(game.get_state().max_heroes, 2);
+
+    let callbacks = game.perform_move(&GameMove::Build(pos, "bandit_camp_small".to_string())).unwrap();
+    assert!(callbacks.contains(&GameCallback::MaxHeroesDecreased { current_max_heroes: 1 }));
+    assert!(callbacks.contains(&GameCallback::ChangedResource { resource: GResource::Gold, new_value: 100 }));
+    // the whole Story of the wilderness is taken off
+    assert_eq!(game.get_resources()[&GResource::Story], 9);
+}
+
+/// Full refunds, and a camp for 3 Story replacing wilderness with the
+/// whole 1 Story of it taken off.
+fn discounted_camp_game(build_time: u32) -> STWGame {
+    game_with(|id, data|match id {
+        "globals" => {
+            data["start_game_resources"] = json!({"Gold": 100, "Story": 10});
+            data["construction_refund"] = json!(1.0);
+            data["demolish_refund"] = json!(1.0);
+        },
+        "bandit_camp_small" => {
+            data["requirments"].as_array_mut().unwrap().push(json!({"ReplacesPath": "forest"}));
+            data["replacement"] = json!({"cost_discount": 1.0});
+            data["cost"] = json!({"Story": 3});
+            data["build_time"] = json!(build_time);
+        },
+        _ => {}
+    })
+}
+
+#[test]
+fn refunds_give_back_the_discounted_cost() {
+    let mut game = discounted_camp_game(0);
+    let pos = wilderness(&mut game);
+    game.perform_move(&GameMove::Build(pos, "bandit_camp_small".to_string())).unwrap();
+    assert_eq!(game.get_resources()[&GResource::Story], 7);
+    game.perform_move(&GameMove::Demolish(pos)).unwrap();
+    assert_eq!(game.get_resources()[&GResource::Story], 9);
+
+    let mut game = discounted_camp_game(2);
+    let pos = wilderness(&mut game);
+    game.perform_move(&GameMove::Build(pos, "bandit_camp_small".to_string())).unwrap();
+    assert_eq!(game.get_constructions()[0].cost, [(GResource::Story, 2)].into());
+    game.perform_move(&GameMove::CancelConstruction(pos)).unwrap();
+    assert_eq!(game.get_resources()[&GResource::Story], 9);
+}
+
+#[test]
+fn replaces_is_rejected_inside_any_or_not() {
+    for requirment in [
+        json!({"Any": [{"Replaces": "wilderness"}, {"HasOrigin": "forest"}]}),
+        json!({"Not": {"ReplacesPath": "forest"}}),
+    ] {
+        let mut resources = load_resources();
+        for resource in resources.iter_mut().filter(|e|e.1["id"] == "bandit_camp_small") {
+            resource.1["data"]["requirments"].as_array_mut().unwrap().push(requirment.clone());
+        }
+        let error = GameConfig::new().set_map_size(37).set_resources(resources).start_game().err().unwrap();
+        assert!(error.msg.contains("bandit_camp_small"), "{}", error.msg);
+    }
+}
+
+#[test]
+fn replacement_keeps_effects_by_default() {
+    let mut game = camp_game(json!({"ReplacesPath": "forest"}), json!({}));
+    let pos = wilderness(&mut game);
+    game.perform_move(&GameMove::Build(pos, "bandit_camp_small".to_string())).unwrap();
+    assert_eq!(game.get_resources()[&GResource::Gold], 105);
+    assert_eq!(game.get_state().max_heroes, 2);
+}
+
+#[test]
+fn replacement_undoes_first_time_effects_where_they_were_played() {
+    let mut game = game_with(|id, data|match id {
+        "globals" => data["start_game_resources"] = json!({"Gold": 100, "Story": 10}),
+        "wilderness" => data["instant_effects"] = json!([{"IncreaseMaxHeroes": {"only_first_time": true}}]),
+        "bandit_camp_small" => {
+            data["requirments"].as_array_mut().unwrap().push(json!({"Replaces": "wilderness"}));
+            data["replacement"] = json!({"undo_effects": true});
+        },
+        _ => {}
+    });
+    let first = wilderness(&mut game);
+    let second = wilderness(&mut game);
+    assert_eq!(game.get_state().max_heroes, 2);
+
+    let callbacks = game.perform_move(&GameMove::Build(second, "bandit_camp_small".to_string())).unwrap();
+    assert!(!callbacks.iter().any(|e|matches!(e, GameCallback::MaxHeroesDecreased { .. })));
+    let callbacks = game.perform_move(&GameMove::Build(first, "bandit_camp_small".to_string())).unwrap();
+    assert!(callbacks.contains(&GameCallback::MaxHeroesDecreased { current_max_heroes: 1 }));
+}
@@ -0,0 +1,61 @@
+mod common;
+use stw_game_lib::{game::{map::TilePos, game_move::GameMove, replay::{Replay, ReplayError}, STWGame}, GameConfig};
+use crate::common::load_resources;
+
+fn play_sample_game() -> STWGame {
+    let mut game = GameConfig::new()
+        .set_map_size(37)
+        .set_resources(load_resources())
+        .set_seed("replay seed")
+        .start_game()
+        .unwrap();
+
+    game.perform_move(&GameMove::Build(TilePos { q: 8, r: 18 }, "cottage".to_string())).unwrap();
+    assert!(game.perform_move(&GameMove::Build(TilePos { q: 9, r: 18 }, "village".to_string())).is_err());
+    game.perform_move(&GameMove::Wait).unwrap();
+    game.perform_move(&GameMove::Wait).unwrap();
+    game.perform_move(&GameMove::StartHistory(TilePos { q: 8, r: 18 }, 0)).unwrap();
+    game.perform_move(&GameMove::PlayMove(TilePos { q: 9, r: 18 }, "shopping_village".to_string())).unwrap();
+    game.perform_move(&GameMove::PlayMove(TilePos { q: 9, r: 17 }, "gathering_meadow".to_string())).unwrap();
+    game
+}
+
+#[test]
+fn replay_records_successful_moves() {
+    let game = play_sample_game();
+    let replay = game.get_replay();
+    assert_eq!(replay.seed, "replay seed");
+    assert_eq!(replay.map_size, 37);
+    assert_eq!(replay.steps.len(), 6);
+    assert_eq!(replay.steps[0].game_move, GameMove::Build(TilePos { q: 8, r: 18 }, "cottage".to_string()));
+}
+
+#[test]
+fn replay_reproduces_game() {
+    let game = play_sample_game();
+    let text = serde_json::to_string(game.get_replay()).unwrap();
+    let replay: Replay = serde_json::from_str(&text).unwrap();
+
+    let replayed = replay.play(load_resources()).unwrap();
+    assert_eq!(replayed.get_replay(), game.get_replay());
+}
+
+#[test]
+fn replay_reports_first_divergence() {
+    let mut replay = play_sample_game().get_replay().clone();
+    replay.steps[3].callbacks.clear();
+    replay.steps[5].callbacks.clear();
+
+    match replay.play(load_resources()) {
+        Err(ReplayError::Divergence { step, .. }) => assert_eq!(step, 3),
+        other => panic!("Expected divergence, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn replay_rejects_other_resources() {
+    let replay = play_sample_game().get_replay().clone();
+    let mut resources = load_resources();
+    resources.pop();
+    assert!(matches!(replay.play(resources), Err(ReplayError::Game(_))));
+}
@@ -0,0 +1,43 @@
+mod common;
+use stw_game_lib::{game::STWGame, strategy::{autoplay, Strategy, RandomStrategy, GreedyBuilder, QuestPlanner, StopReason}, GameConfig};
+use crate::common::load_resources;
+
+fn prepare_game(seed: &str) -> STWGame {
+    GameConfig::new()
+        .set_map_size(37)
+        .set_resources(load_resources())
+        .set_seed(seed)
+        .start_game()
+        .unwrap()
+}
+
+#[test]
+fn strategies_play_legal_moves() {
+    let strategies: Vec<Box<dyn Strategy>> = vec![
+        Box::new(RandomStrategy::new("bot")),
+        Box::new(GreedyBuilder::default()),
+        Box::new(QuestPlanner::default())
+    ];
+    for strategy in strategies {
+        for seed in ["a", "b", "c"] {
+            let summary = autoplay(&mut prepare_game(seed), strategy.as_ref(), 30);
+            println!("{:?}", summary);
+            assert!(!matches!(summary.stop_reason, StopReason::IllegalMove { .. }), "{:?}", summary);
+        }
+    }
+}
+
+#[test]
+fn greedy_builder_builds() {
+    let summary = autoplay(&mut prepare_game("greedy"), &GreedyBuilder::default(), 20);
+    assert_eq!(summary.stop_reason, StopReason::MaxTurns);
+    assert_eq!(summary.turns, 20);
+    assert!(summary.buildings.values().sum::<u32>() > 1);
+}
+
+#[test]
+fn autoplay_is_deterministic() {
+    let first = autoplay(&mut prepare_game("same"), &RandomStrategy::new("bot"), 20);
+    let second = autoplay(&mut prepare_game("same"), &RandomStrategy::new("bot"), 20);
+    assert_eq!(first, second);
+}
@@ -0,0 +1,44 @@
+mod common;
+use stw_game_lib::{game::{STWGame, game_controller::GameCallback, summary::GameSummary}, resource::enums::GResource, strategy::{autoplay, QuestPlanner}, GameConfig};
+use crate::common::load_resources;
+
+fn played_game() -> STWGame {
+    let mut game = GameConfig::new()
+        .set_map_size(37)
+        .set_resources(load_resources())
+        .set_seed("summary")
+        .start_game()
+        .unwrap();
+    autoplay(&mut game, &QuestPlanner::default(), 40);
+    game
+}
+
+#[test]
+fn summary_matches_the_game() {
+    let game = played_game();
+    let summary = game.get_summary();
+    let callbacks: Vec<&GameCallback> = game.get_replay().steps.iter().flat_map(|e|e.callbacks.iter()).collect();
+
+    assert_eq!(summary.turns, game.get_turn());
+    assert_eq!(summary.buildings.len(), callbacks.iter().filter(|e|matches!(e, GameCallback::NewTileContent { .. })).count());
+    assert_eq!(summary.quests_completed.len(), callbacks.iter().filter(|e|matches!(e, GameCallback::QuestFinished { success: true, .. })).count());
+    assert_eq!(summary.heroes_recruited - summary.heroes_lost, game.get_heroes().len() as u32);
+    assert_eq!(summary.outcome.as_ref(), game.get_outcome());
+    let start = 5; // Gold in res/globals.json
+    assert_eq!(start + summary.resources_earned.get(&GResource::Gold).unwrap_or(&0) - summary.resources_spent.get(&GResource::Gold).unwrap_or(&0),
+        game.get_resources()[&GResource::Gold]);
+    assert_eq!(summary.score, summary.score_parts.values().sum::<f32>().max(0.0).round() as u32);
+    assert_eq!(summary.score, game.get_score());
+
+    let text = summary.to_string();
+    assert!(text.contains(&format!("Score: {}", summary.score)));
+    let json: GameSummary = serde_json::from_str(&summary.to_json().unwrap()).unwrap();
+    assert_eq!(json, summary);
+}
+
+#[test]
+fn replay_gives_the_same_summary() {
+    let game = played_game();
+    let replayed = game.get_replay().play(load_resources()).unwrap();
+    assert_eq!(replayed.get_summary(), game.get_summary());
+}
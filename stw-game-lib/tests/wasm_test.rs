@@ -0,0 +1,41 @@
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use stw_game_lib::wasm::{JsGame, JsGameConfig};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn prepare_game() -> JsGame{
+    let mut config = JsGameConfig::new().unwrap();
+    config.set_seed("test seed");
+    config.set_map_size(37);
+    config.start_game().unwrap()
+}
+
+fn from_js(value: wasm_bindgen::JsValue) -> Value{
+    serde_wasm_bindgen::from_value(value).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn test_build() {
+    let mut game = prepare_game();
+    let buildings = from_js(game.possible_buildings(8, 18).unwrap());
+    assert_eq!(buildings[0]["id"], "cottage");
+
+    let build = json!({"type": "Build", "data": [{"q": 8, "r": 18}, "cottage"]})
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .unwrap();
+    let res = from_js(game.perform_move(build.clone()).unwrap());
+    assert!(res["callbacks"].as_array().unwrap().contains(&json!({"type": "NewTileContent", "data": {"position": {"q": 8, "r": 18}, "field_type_id": "cottage"}})));
+    let res = from_js(game.perform_move(build).unwrap());
+    assert!(res["bad_move"]["msg"].is_string());
+
+    assert_eq!(from_js(game.tile(8, 18).unwrap())["content"], "cottage");
+    assert!(game.tile(-100, -100).unwrap().is_undefined());
+    assert_eq!(from_js(game.possible_hero_moves().unwrap()), json!([]));
+}
+
+#[wasm_bindgen_test]
+fn test_same_seed_same_game() {
+    assert_eq!(from_js(prepare_game().state().unwrap()), from_js(prepare_game().state().unwrap()));
+}
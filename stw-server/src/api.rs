@@ -0,0 +1,172 @@
+use axum::{Router, Json, routing::get, extract::{Path, State, WebSocketUpgrade, ws::{Message, WebSocket}}, response::{IntoResponse, Response}, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use stw_game_lib::{game::{game_move::GameMove, game_controller::GameCallback, state::GameState, map::TilePos, explain::BuildingExplanation}, GameConfig};
+use tokio::sync::broadcast;
+
+use crate::host::{GameHost, JobPanicked};
+
+pub enum ApiError{
+    NotFound(String),
+    BadRequest(String),
+    BadMove(Value),
+    Conflict(String),
+    Internal(String)
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        match self {
+            ApiError::NotFound(id) => (StatusCode::NOT_FOUND, Json(json!({"msg": format!("No game with id {}", id)}))).into_response(),
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, Json(json!({"msg": msg}))).into_response(),
+            ApiError::BadMove(bad_move) => (StatusCode::BAD_REQUEST, Json(bad_move)).into_response(),
+            ApiError::Conflict(msg) => (StatusCode::CONFLICT, Json(json!({"msg": msg}))).into_response(),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"msg": msg}))).into_response(),
+        }
+    }
+}
+
+impl From<JobPanicked> for ApiError {
+    fn from(_: JobPanicked) -> Self {
+        ApiError::Internal("Request crashed while running on the game host".to_string())
+    }
+}
+
+#[derive(Deserialize, Default)]
+pub struct NewGame{
+    pub seed: Option<String>,
+    pub size: Option<u32>,
+    pub name: Option<String>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GameInfo{
+    pub id: String,
+    pub name: String,
+    pub turn: u32
+}
+
+pub fn router(host: GameHost) -> Router{
+    Router::new()
+        .route("/games", get(list_games).post(create_game))
+        .route("/games/{id}", get(get_game).delete(delete_game))
+        .route("/games/{id}/moves", get(legal_moves).post(perform_move))
+        .route("/games/{id}/explain/{q}/{r}/{building}", get(explain_building))
+        .route("/games/{id}/save", axum::routing::post(save_game))
+        .route("/games/{id}/ws", get(callbacks_socket))
+        .with_state(host)
+}
+
+async fn list_games(State(host): State<GameHost>) -> Result<Json<Vec<GameInfo>>, ApiError>{
+    Ok(Json(host.run(|sessions|sessions.games.iter()
+        .map(|e|GameInfo {
+            id: e.0.to_string(),
+            name: e.1.game.get_replay().name.to_string(),
+            turn: e.1.game.get_turn()
+        })
+        .collect()
+    ).await?))
+}
+
+async fn create_game(State(host): State<GameHost>, body: Option<Json<NewGame>>) -> Result<(StatusCode, Json<Value>), ApiError>{
+    let Json(new_game) = body.unwrap_or_default();
+    if new_game.size == Some(0) {
+        return Err(ApiError::BadRequest("Map size must be at least 1".to_string()));
+    }
+    host.run(move |sessions|{
+        let mut config = GameConfig::new();
+        if let Some(seed) = &new_game.seed {
+            config = config.set_seed(seed);
+        }
+        if let Some(size) = new_game.size {
+            config = config.set_map_size(size);
+        }
+        if let Some(name) = &new_game.name {
+            config = config.set_name(name);
+        }
+        let id = sessions.create(config).map_err(|e|ApiError::BadRequest(e.msg))?;
+        let seed = sessions.games[&id].game.get_replay().seed.to_string();
+        Ok((StatusCode::CREATED, Json(json!({"id": id, "seed": seed}))))
+    }).await?
+}
+
+async fn get_game(State(host): State<GameHost>, Path(id): Path<String>) -> Result<Json<GameState>, ApiError>{
+    host.run(move |sessions|match sessions.games.get(&id) {
+        Some(session) => Ok(Json(session.game.get_state())),
+        None => Err(ApiError::NotFound(id)),
+    }).await?
+}
+
+async fn delete_game(State(host): State<GameHost>, Path(id): Path<String>) -> Result<StatusCode, ApiError>{
+    host.run(move |sessions|match sessions.remove(&id) {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(ApiError::NotFound(id)),
+        Err(e) => Err(ApiError::Internal(e.msg)),
+    }).await?
+}
+
+async fn legal_moves(State(host): State<GameHost>, Path(id): Path<String>) -> Result<Json<Vec<GameMove>>, ApiError>{
+    host.run(move |sessions|match sessions.games.get(&id) {
+        Some(session) => Ok(Json(session.game.get_possible_moves())),
+        None => Err(ApiError::NotFound(id)),
+    }).await?
+}
+
+async fn explain_building(State(host): State<GameHost>, Path((id, q, r, building)): Path<(String, i32, i32, String)>) -> Result<Json<BuildingExplanation>, ApiError>{
+    host.run(move |sessions|match sessions.games.get(&id) {
+        Some(session) => session.game.explain_building(&TilePos { q, r }, &building)
+            .map(Json)
+            .map_err(|e|ApiError::BadMove(json!(e))),
+        None => Err(ApiError::NotFound(id)),
+    }).await?
+}
+
+async fn perform_move(State(host): State<GameHost>, Path(id): Path<String>, Json(game_move): Json<GameMove>) -> Result<Json<Value>, ApiError>{
+    host.run(move |sessions|{
+        let session = sessions.games.get_mut(&id).ok_or(ApiError::NotFound(id))?;
+        match session.game.perform_move(&game_move) {
+            Ok(callbacks) => {
+                callbacks.iter().for_each(|e|{
+                    let _ = session.events.send(e.clone());
+                });
+                Ok(Json(json!({"callbacks": callbacks})))
+            },
+            Err(e) => Err(ApiError::BadMove(json!(e))),
+        }
+    }).await?
+}
+
+async fn save_game(State(host): State<GameHost>, Path(id): Path<String>) -> Result<Json<Value>, ApiError>{
+    host.run(move |sessions|{
+        if !sessions.games.contains_key(&id) {
+            return Err(ApiError::NotFound(id));
+        }
+        match sessions.save(&id) {
+            Ok(Some(path)) => Ok(Json(json!({"path": path}))),
+            Ok(None) => Err(ApiError::Conflict("Server was started without --save-dir".to_string())),
+            Err(e) => Err(ApiError::Internal(e.msg)),
+        }
+    }).await?
+}
+
+async fn callbacks_socket(State(host): State<GameHost>, Path(id): Path<String>, ws: WebSocketUpgrade) -> Result<Response, ApiError>{
+    let events = host.run(move |sessions|match sessions.games.get(&id) {
+        Some(session) => Ok(session.events.subscribe()),
+        None => Err(ApiError::NotFound(id)),
+    }).await??;
+    Ok(ws.on_upgrade(move |socket|push_callbacks(socket, events)))
+}
+
+async fn push_callbacks(mut socket: WebSocket, mut events: broadcast::Receiver<GameCallback>){
+    loop {
+        let callback = match events.recv().await {
+            Ok(callback) => callback,
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+        let text = serde_json::to_string(&callback).expect("GameCallback is always serializable");
+        if socket.send(Message::Text(text.into())).await.is_err() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::{collections::BTreeMap, fs, panic::{self, AssertUnwindSafe}, path::PathBuf, sync::mpsc, thread};
+
+use stw_game_lib::{game::{STWGame, game_controller::GameCallback, replay::Replay, GameError}, GameConfig};
+use tokio::sync::{broadcast, oneshot};
+
+const EVENTS_CAPACITY: usize = 256;
+
+pub struct Session{
+    pub game: STWGame,
+    pub events: broadcast::Sender<GameCallback>
+}
+
+/// All games hosted by the server. Lives on the host thread only, because
+/// `STWGame` is not `Send`.
+pub struct Sessions{
+    pub games: BTreeMap<String, Session>,
+    pub resources: Vec<(String, serde_json::Value)>,
+    pub save_dir: Option<PathBuf>,
+    next_id: u64
+}
+
+impl Sessions {
+    fn new(resources: Vec<(String, serde_json::Value)>, save_dir: Option<PathBuf>) -> Sessions{
+        Sessions { games: BTreeMap::new(), resources, save_dir, next_id: 1 }
+    }
+
+    pub fn create(&mut self, config: GameConfig) -> Result<String, GameError>{
+        let game = config.set_resources(self.resources.clone()).start_game()?;
+        while self.games.contains_key(&self.next_id.to_string()) {
+            self.next_id += 1;
+        }
+        let id = self.next_id.to_string();
+        self.insert(&id, game);
+        Ok(id)
+    }
+
+    pub fn save(&self, id: &str) -> Result<Option<PathBuf>, GameError>{
+        let (Some(dir), Some(session)) = (&self.save_dir, self.games.get(id)) else {
+            return Ok(None);
+        };
+        let path = dir.join(format!("{}.json", id));
+        session.game.get_replay().save_to_file(&path.to_string_lossy())?;
+        Ok(Some(path))
+    }
+
+    /// Removes the game together with its save, so it is not restored on the
+    /// next start. `false` when there is no such game.
+    pub fn remove(&mut self, id: &str) -> Result<bool, GameError>{
+        if self.games.remove(id).is_none() {
+            return Ok(false);
+        }
+        if let Some(dir) = &self.save_dir {
+            let path = dir.join(format!("{}.json", id));
+            match fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound =>
+                    return Err(GameError::new(format!("Can not remove save {:?}: {}", path, e))),
+                _ => {}
+            }
+        }
+        Ok(true)
+    }
+
+    /// Restores every game saved in `save_dir`, keyed by file name.
+    fn load_saved(&mut self) -> Result<(), GameError>{
+        let Some(dir) = self.save_dir.clone() else {
+            return Ok(());
+        };
+        let entries = fs::read_dir(&dir)
+            .map_err(|e|GameError::new(format!("Can not read save directory {:?}: {}", dir, e)))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|e|e != "json") {
+                continue;
+            }
+            let id = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            let game = Replay::load_from_file(&path.to_string_lossy())?
+                .play(self.resources.clone())
+                .map_err(|e|GameError::new(format!("Can not restore {:?}: {}", path, e)))?;
+            self.insert(&id, game);
+        }
+        Ok(())
+    }
+
+    fn insert(&mut self, id: &str, game: STWGame){
+        let (events, _) = broadcast::channel(EVENTS_CAPACITY);
+        self.games.insert(id.to_string(), Session { game, events });
+    }
+}
+
+type Job = Box<dyn FnOnce(&mut Sessions) + Send>;
+
+/// Returned by `GameHost::run` when the job panicked. Other jobs and
+/// sessions are not affected.
+#[derive(Debug)]
+pub struct JobPanicked;
+
+/// Handle to the thread that owns all sessions. Handlers send it closures and
+/// await their results.
+#[derive(Clone)]
+pub struct GameHost{
+    jobs: mpsc::Sender<Job>
+}
+
+impl GameHost {
+    /// Starts the host thread with games restored from `save_dir`, if given.
+    pub fn start(resources: Vec<(String, serde_json::Value)>, save_dir: Option<PathBuf>) -> Result<GameHost, GameError>{
+        let (jobs, receiver) = mpsc::channel::<Job>();
+        let (started, start_result) = mpsc::channel();
+        thread::spawn(move ||{
+            let mut sessions = Sessions::new(resources, save_dir);
+            if let Err(e) = sessions.load_saved() {
+                let _ = started.send(Err(e));
+                return;
+            }
+            let _ = started.send(Ok(()));
+            for job in receiver {
+                job(&mut sessions);
+            }
+        });
+        start_result.recv().expect("Game host thread stopped")?;
+        Ok(GameHost { jobs })
+    }
+
+    pub async fn run<T, F>(&self, f: F) -> Result<T, JobPanicked>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Sessions) -> T + Send + 'static
+    {
+        let (sender, receiver) = oneshot::channel();
+        self.jobs.send(Box::new(move |sessions|{
+            // a panicking job drops `sender`, so only its caller gets an error
+            if let Ok(res) = panic::catch_unwind(AssertUnwindSafe(||f(sessions))) {
+                let _ = sender.send(res);
+            }
+        })).expect("Game host thread stopped");
+        receiver.await.map_err(|_|JobPanicked)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn host_survives_panicking_job() {
+        let host = GameHost::start(vec![], None).unwrap();
+        assert!(host.run(|_|panic!("bad job")).await.is_err());
+        assert_eq!(host.run(|sessions|sessions.games.len()).await.unwrap(), 0);
+    }
+}
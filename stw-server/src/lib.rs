@@ -0,0 +1,15 @@
+mod api;
+mod host;
+
+use std::path::PathBuf;
+
+use axum::Router;
+use stw_game_lib::game::GameError;
+
+pub use api::{GameInfo, NewGame};
+use host::GameHost;
+
+/// Builds the server with games restored from `save_dir`, if given.
+pub fn app(resources: Vec<(String, serde_json::Value)>, save_dir: Option<PathBuf>) -> Result<Router, GameError>{
+    Ok(api::router(GameHost::start(resources, save_dir)?))
+}
@@ -0,0 +1,52 @@
+use std::{env, path::PathBuf, process};
+
+use stw_game_lib::resource::load_resources_from_dir;
+
+const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+const USAGE: &str = "Usage: stw-server [--addr HOST:PORT] [--resources DIR] [--save-dir DIR]";
+
+struct Args{
+    addr: String,
+    resources: String,
+    save_dir: Option<PathBuf>
+}
+
+fn parse_args() -> Result<Args, String>{
+    let mut res = Args {
+        addr: DEFAULT_ADDR.to_string(),
+        resources: concat!(env!("CARGO_MANIFEST_DIR"), "/../stw-game-lib/res").to_string(),
+        save_dir: None
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = ||args.next().ok_or_else(||format!("Missing value for {}", arg));
+        match arg.as_str() {
+            "--addr" => res.addr = value()?,
+            "--resources" => res.resources = value()?,
+            "--save-dir" => res.save_dir = Some(PathBuf::from(value()?)),
+            other => return Err(format!("Unknown argument {}", other)),
+        }
+    }
+    Ok(res)
+}
+
+#[tokio::main]
+async fn main() {
+    let args = parse_args().unwrap_or_else(|e|{
+        eprintln!("{}\n{}", e, USAGE);
+        process::exit(2);
+    });
+    let app = load_resources_from_dir(&args.resources)
+        .and_then(|resources|stw_server::app(resources, args.save_dir))
+        .unwrap_or_else(|e|{
+            eprintln!("{}", e);
+            process::exit(1);
+        });
+
+    let listener = tokio::net::TcpListener::bind(&args.addr).await.unwrap_or_else(|e|{
+        eprintln!("Can not listen on {}: {}", args.addr, e);
+        process::exit(1);
+    });
+    println!("stw-server listening on http://{}", args.addr);
+    axum::serve(listener, app).await.expect("Server stopped");
+}
@@ -0,0 +1,141 @@
+use std::path::PathBuf;
+
+use futures_util::StreamExt;
+use serde_json::{json, Value};
+use stw_game_lib::resource::load_resources_from_dir;
+use tokio_tungstenite::tungstenite::Message;
+
+const RESOURCES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../stw-game-lib/res");
+
+async fn start_server(save_dir: Option<PathBuf>) -> String{
+    let app = stw_server::app(load_resources_from_dir(RESOURCES).unwrap(), save_dir).unwrap();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("127.0.0.1:{}", addr.port())
+}
+
+async fn create_game(client: &reqwest::Client, addr: &str) -> String{
+    let res = client.post(format!("http://{}/games", addr))
+        .json(&json!({"seed": "test seed", "size": 37, "name": "server test"}))
+        .send().await.unwrap();
+    assert_eq!(res.status(), 201);
+    let body: Value = res.json().await.unwrap();
+    assert_eq!(body["seed"], "test seed");
+    body["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn bad_config_is_rejected() {
+    let addr = start_server(None).await;
+    let client = reqwest::Client::new();
+    let res = client.post(format!("http://{}/games", addr))
+        .json(&json!({"size": 0}))
+        .send().await.unwrap();
+    assert_eq!(res.status(), 400);
+
+    let res = client.get(format!("http://{}/games", addr)).send().await.unwrap();
+    assert_eq!(res.status(), 200);
+}
+
+#[tokio::test]
+async fn rest_api_plays_game() {
+    let addr = start_server(None).await;
+    let client = reqwest::Client::new();
+    let id = create_game(&client, &addr).await;
+
+    let games: Value = client.get(format!("http://{}/games", addr)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(games, json!([{"id": id, "name": "server test", "turn": 0}]));
+
+    let moves: Value = client.get(format!("http://{}/games/{}/moves", addr, id)).send().await.unwrap().json().await.unwrap();
+    assert!(moves.as_array().unwrap().contains(&json!({"type": "Build", "data": [{"q": 8, "r": 18}, "cottage"]})));
+
+    let res = client.post(format!("http://{}/games/{}/moves", addr, id))
+        .json(&json!({"type": "Build", "data": [{"q": 9, "r": 18}, "village"]}))
+        .send().await.unwrap();
+    assert_eq!(res.status(), 400);
+
+    let explanation: Value = client.get(format!("http://{}/games/{}/explain/9/18/village", addr, id)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(explanation["buildable"], false);
+    assert_eq!(explanation["shortfalls"], json!({"Gold": 15, "GreenTrophy": 1}));
+
+    let res = client.post(format!("http://{}/games/{}/moves", addr, id))
+        .json(&json!({"type": "Build", "data": [{"q": 8, "r": 18}, "cottage"]}))
+        .send().await.unwrap();
+    assert_eq!(res.status(), 200);
+
+    let state: Value = client.get(format!("http://{}/games/{}", addr, id)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(state["resources"]["Gold"], 3);
+
+    let res = client.post(format!("http://{}/games/{}/save", addr, id)).send().await.unwrap();
+    assert_eq!(res.status(), 409);
+
+    let res = client.delete(format!("http://{}/games/{}", addr, id)).send().await.unwrap();
+    assert_eq!(res.status(), 204);
+    let res = client.get(format!("http://{}/games/{}", addr, id)).send().await.unwrap();
+    assert_eq!(res.status(), 404);
+}
+
+#[tokio::test]
+async fn websocket_pushes_callbacks() {
+    let addr = start_server(None).await;
+    let client = reqwest::Client::new();
+    let id = create_game(&client, &addr).await;
+
+    let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/games/{}/ws", addr, id)).await.unwrap();
+
+    client.post(format!("http://{}/games/{}/moves", addr, id))
+        .json(&json!({"type": "Build", "data": [{"q": 8, "r": 18}, "cottage"]}))
+        .send().await.unwrap();
+
+    let mut pushed = vec![];
+    while pushed.len() < 2 {
+        if let Message::Text(text) = socket.next().await.unwrap().unwrap() {
+            pushed.push(serde_json::from_str::<Value>(&text).unwrap());
+        }
+    }
+    assert_eq!(pushed[0], json!({"type": "ChangedResource", "data": {"resource": "Gold", "new_value": 3}}));
+    assert_eq!(pushed[1]["type"], "NewTileContent");
+}
+
+#[tokio::test]
+async fn saved_games_survive_restart() {
+    let save_dir = std::env::temp_dir().join(format!("stw-server-test-{}", std::process::id()));
+    std::fs::create_dir_all(&save_dir).unwrap();
+
+    let addr = start_server(Some(save_dir.clone())).await;
+    let client = reqwest::Client::new();
+    let id = create_game(&client, &addr).await;
+    client.post(format!("http://{}/games/{}/moves", addr, id))
+        .json(&json!({"type": "Build", "data": [{"q": 8, "r": 18}, "cottage"]}))
+        .send().await.unwrap();
+    let state: Value = client.get(format!("http://{}/games/{}", addr, id)).send().await.unwrap().json().await.unwrap();
+    let res = client.post(format!("http://{}/games/{}/save", addr, id)).send().await.unwrap();
+    assert_eq!(res.status(), 200);
+
+    let restarted = start_server(Some(save_dir.clone())).await;
+    let restored: Value = client.get(format!("http://{}/games/{}", restarted, id)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(restored, state);
+
+    std::fs::remove_dir_all(save_dir).unwrap();
+}
+
+#[tokio::test]
+async fn deleted_games_stay_deleted_after_restart() {
+    let save_dir = std::env::temp_dir().join(format!("stw-server-delete-test-{}", std::process::id()));
+    std::fs::create_dir_all(&save_dir).unwrap();
+
+    let addr = start_server(Some(save_dir.clone())).await;
+    let client = reqwest::Client::new();
+    let id = create_game(&client, &addr).await;
+    client.post(format!("http://{}/games/{}/save", addr, id)).send().await.unwrap();
+    let res = client.delete(format!("http://{}/games/{}", addr, id)).send().await.unwrap();
+    assert_eq!(res.status(), 204);
+    assert!(!save_dir.join(format!("{}.json", id)).exists());
+
+    let restarted = start_server(Some(save_dir.clone())).await;
+    let res = client.get(format!("http://{}/games/{}", restarted, id)).send().await.unwrap();
+    assert_eq!(res.status(), 404);
+
+    std::fs::remove_dir_all(save_dir).unwrap();
+}
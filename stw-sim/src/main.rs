@@ -0,0 +1,140 @@
+mod metrics;
+mod stats;
+
+use std::{env, fs, panic, process, thread, sync::atomic::{AtomicUsize, Ordering}};
+
+use metrics::{SimConfig, StrategyKind, WinPath, GameMetrics, play_game};
+use serde_json::json;
+use stw_game_lib::resource::load_resources_from_dir;
+
+const USAGE: &str = "Usage: stw-sim [--games N] [--turns T] [--strategy random|greedy|quest] [--seed PREFIX] \
+[--size SIZE] [--threads N] [--sample-every T] [--format json|csv] [--per-game] [--output FILE] [--resources DIR]";
+
+struct Args{
+    games: usize,
+    threads: usize,
+    format: String,
+    per_game: bool,
+    output: Option<String>,
+    resources: String,
+    config: SimConfig
+}
+
+fn parse_number<T: std::str::FromStr>(value: String, what: &str) -> Result<T, String>{
+    value.parse().map_err(|_|format!("{} has to be a number", what))
+}
+
+fn parse_args() -> Result<Args, String>{
+    let mut res = Args {
+        games: 1000,
+        threads: thread::available_parallelism().map_or(1, |e|e.get()),
+        format: "json".to_string(),
+        per_game: false,
+        output: None,
+        resources: concat!(env!("CARGO_MANIFEST_DIR"), "/../stw-game-lib/res").to_string(),
+        config: SimConfig {
+            resources: vec![],
+            strategy: StrategyKind::Greedy,
+            seed: "sim".to_string(),
+            map_size: None,
+            turns: 200,
+            sample_every: 10
+        }
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = ||args.next().ok_or_else(||format!("Missing value for {}", arg));
+        match arg.as_str() {
+            "--games" => res.games = parse_number(value()?, "Games")?,
+            "--turns" => res.config.turns = parse_number(value()?, "Turns")?,
+            "--strategy" => res.config.strategy = StrategyKind::parse(&value()?)?,
+            "--seed" => res.config.seed = value()?,
+            "--size" => res.config.map_size = Some(parse_number(value()?, "Size")?),
+            "--threads" => res.threads = parse_number::<usize>(value()?, "Threads")?.max(1),
+            "--sample-every" => res.config.sample_every = parse_number::<u32>(value()?, "Sample interval")?.max(1),
+            "--format" => res.format = match value()?.as_str() {
+                f @ ("json" | "csv") => f.to_string(),
+                other => return Err(format!("Unknown format {}", other)),
+            },
+            "--per-game" => res.per_game = true,
+            "--output" => res.output = Some(value()?),
+            "--resources" => res.resources = value()?,
+            other => return Err(format!("Unknown argument {}", other)),
+        }
+    }
+    Ok(res)
+}
+
+/// Plays all games on `threads` workers. Games hold `Rc`s, so each one is
+/// created and finished inside its worker.
+fn run(args: &Args, win_path: &WinPath) -> Vec<GameMetrics>{
+    let next = AtomicUsize::new(0);
+    let mut res: Vec<(usize, GameMetrics)> = thread::scope(|scope|{
+        let workers: Vec<_> = (0..args.threads.min(args.games.max(1)))
+            .map(|_|scope.spawn(||{
+                let mut played = vec![];
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    if index >= args.games {
+                        break played;
+                    }
+                    played.push((index, play_game(&args.config, index, win_path)));
+                }
+            }))
+            .collect();
+        workers.into_iter().flat_map(|e|e.join().unwrap_or_default()).collect()
+    });
+    res.sort_by_key(|e|e.0);
+    res.into_iter().map(|e|e.1).collect()
+}
+
+fn main() {
+    let mut args = parse_args().unwrap_or_else(|e|{
+        eprintln!("{}\n{}", e, USAGE);
+        process::exit(2);
+    });
+    args.config.resources = load_resources_from_dir(&args.resources).unwrap_or_else(|e|{
+        eprintln!("Can not load resources: {}", e.msg);
+        process::exit(1);
+    });
+    // fail fast on broken resources instead of reporting every game as crashed
+    if let Err(e) = stw_game_lib::GameConfig::new().set_resources(args.config.resources.clone()).start_game() {
+        eprintln!("Can not start a game: {}", e.msg);
+        process::exit(1);
+    }
+
+    // crashed games keep their panic in the metrics instead of printing it
+    panic::set_hook(Box::new(metrics::record_panic));
+    let win_path = WinPath::from_resources(&args.config.resources);
+    let games = run(&args, &win_path);
+    drop(panic::take_hook());
+    let aggregated = stats::aggregate(&games);
+
+    let text = match args.format.as_str() {
+        "csv" => {
+            let mut lines = vec![stats::CSV_HEADER.to_string()];
+            lines.extend(aggregated.iter().map(|e|e.1.to_csv_row(e.0)));
+            lines.join("\n") + "\n"
+        },
+        _ => {
+            let mut value = json!({
+                "games": games.len(),
+                "turns": args.config.turns,
+                "strategy": args.config.strategy,
+                "seed": args.config.seed,
+                "metrics": aggregated
+            });
+            if args.per_game {
+                value["per_game"] = json!(games);
+            }
+            serde_json::to_string_pretty(&value).unwrap_or_default() + "\n"
+        },
+    };
+    match &args.output {
+        Some(path) => fs::write(path, text).unwrap_or_else(|e|{
+            eprintln!("Can not write {}: {}", path, e);
+            process::exit(1);
+        }),
+        None => print!("{}", text),
+    }
+}
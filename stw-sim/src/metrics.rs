@@ -0,0 +1,216 @@
+use std::{cell::RefCell, collections::BTreeMap, panic::{self, AssertUnwindSafe, PanicHookInfo}};
+
+use serde::Serialize;
+use stw_game_lib::{
+    game::{STWGame, game_controller::GameCallback},
+    resource::enums::{GResource, HeroSkill},
+    strategy::{autoplay_with, Strategy, RandomStrategy, GreedyBuilder, QuestPlanner},
+    GameConfig
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum StrategyKind{
+    Random,
+    Greedy,
+    Quest
+}
+
+impl StrategyKind {
+    pub fn parse(name: &str) -> Result<StrategyKind, String>{
+        match name {
+            "random" => Ok(StrategyKind::Random),
+            "greedy" => Ok(StrategyKind::Greedy),
+            "quest" => Ok(StrategyKind::Quest),
+            other => Err(format!("Unknown strategy {} - use random, greedy or quest", other)),
+        }
+    }
+
+    fn create(&self, seed: &str) -> Box<dyn Strategy>{
+        match self {
+            StrategyKind::Random => Box::new(RandomStrategy::new(seed)),
+            StrategyKind::Greedy => Box::new(GreedyBuilder::default()),
+            StrategyKind::Quest => Box::new(QuestPlanner::default()),
+        }
+    }
+}
+
+pub struct SimConfig{
+    pub resources: Vec<(String, serde_json::Value)>,
+    pub strategy: StrategyKind,
+    pub seed: String,
+    pub map_size: Option<u32>,
+    pub turns: u32,
+    pub sample_every: u32
+}
+
+/// `path_level` of every building on `Globals::win_cond_build_path`, read
+/// straight from the resource json.
+pub struct WinPath{
+    pub levels: BTreeMap<String, u32>
+}
+
+impl WinPath {
+    pub fn from_resources(resources: &[(String, serde_json::Value)]) -> WinPath{
+        let path = resources.iter()
+            .find(|e|e.0 == "Globals")
+            .and_then(|e|e.1["data"]["win_cond_build_path"].as_str())
+            .unwrap_or_default();
+        let levels = resources.iter()
+            .filter(|e|e.0 == "FieldTypeData" && e.1["data"]["build_path"] == path)
+            .filter_map(|e|Some((e.1["id"].as_str()?.to_string(), e.1["data"]["path_level"].as_u64()? as u32)))
+            .collect();
+        WinPath { levels }
+    }
+
+    pub fn tiers(&self) -> Vec<u32>{
+        let mut tiers: Vec<u32> = self.levels.values().copied().collect();
+        tiers.sort();
+        tiers.dedup();
+        tiers
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct GameMetrics{
+    pub seed: String,
+    pub crashed: bool,
+    /// Message and location of the panic of a crashed game.
+    pub panic: Option<String>,
+    pub turns: u32,
+    /// First turn with a building of each win path level.
+    pub tier_turns: BTreeMap<u32, u32>,
+    /// Resources at every `sample_every`-th turn.
+    pub resources: BTreeMap<u32, BTreeMap<GResource, u32>>,
+    pub buildings: BTreeMap<String, u32>,
+    pub quests_spawned: BTreeMap<String, u32>,
+    pub quests_succeeded: BTreeMap<String, u32>,
+    pub quests_failed: BTreeMap<String, u32>,
+    pub heroes_recruited: u32,
+    pub hero_deaths: u32,
+    /// Skill points gained from `eternal_modificator`, summed over heroes.
+    pub skill_growth: BTreeMap<HeroSkill, f32>
+}
+
+impl GameMetrics {
+    fn reach_level(&mut self, level: u32, turn: u32, win_path: &WinPath){
+        for tier in win_path.tiers().into_iter().filter(|e|*e <= level) {
+            self.tier_turns.entry(tier).or_insert(turn);
+        }
+    }
+
+    fn sample(&mut self, game: &STWGame, sample_every: u32){
+        if game.get_turn().is_multiple_of(sample_every.max(1)) {
+            self.resources.entry(game.get_turn())
+                .or_insert_with(||game.get_resources().iter().map(|e|(*e.0, *e.1)).collect());
+        }
+    }
+}
+
+thread_local! {
+    /// Last panic on this thread, kept by `record_panic`.
+    static LAST_PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Panic hook that keeps the message and location for the metrics of the
+/// crashed game instead of printing them.
+pub fn record_panic(info: &PanicHookInfo){
+    let message = info.payload_as_str().unwrap_or("unknown panic");
+    let location = info.location().map_or(String::new(), |e|format!(" at {}:{}:{}", e.file(), e.line(), e.column()));
+    LAST_PANIC.with(|e|*e.borrow_mut() = Some(format!("{}{}", message, location)));
+}
+
+fn hero_skills(game: &STWGame) -> Vec<BTreeMap<HeroSkill, f32>>{
+    game.get_heroes().iter()
+        .map(|e|e.get_skills().iter().map(|s|(*s.0, *s.1)).collect())
+        .collect()
+}
+
+/// Plays game number `index`. A game that panics is reported as crashed with
+/// whatever was collected before, and the panic when `record_panic` is the
+/// panic hook.
+pub fn play_game(config: &SimConfig, index: usize, win_path: &WinPath) -> GameMetrics{
+    let seed = format!("{}-{}", config.seed, index);
+    let mut metrics = GameMetrics { seed: seed.to_string(), ..Default::default() };
+
+    let result = panic::catch_unwind(AssertUnwindSafe(||{
+        let mut game_config = GameConfig::new()
+            .set_resources(config.resources.clone())
+            .set_seed(&seed);
+        if let Some(map_size) = config.map_size {
+            game_config = game_config.set_map_size(map_size);
+        }
+        let mut game = game_config.start_game().expect("Resources were checked before the run");
+
+        for tile in game.get_state().tiles {
+            if let Some(level) = tile.content.and_then(|e|win_path.levels.get(&e).copied()) {
+                metrics.reach_level(level, 0, win_path);
+            }
+        }
+        metrics.sample(&game, config.sample_every);
+        let mut skills = hero_skills(&game);
+
+        let strategy = config.strategy.create(&seed);
+        autoplay_with(&mut game, strategy.as_ref(), config.turns, &mut |game, callbacks|{
+            for callback in callbacks {
+                match callback {
+                    GameCallback::NewTileContent { field_type_id, .. } => {
+                        if let Some(level) = win_path.levels.get(field_type_id) {
+                            metrics.reach_level(*level, game.get_turn(), win_path);
+                        }
+                    },
+                    GameCallback::NewQuest { quest_id, .. } =>
+                        *metrics.quests_spawned.entry(quest_id.to_string()).or_insert(0) += 1,
+                    GameCallback::QuestFinished { quest_id, success: true, .. } =>
+                        *metrics.quests_succeeded.entry(quest_id.to_string()).or_insert(0) += 1,
+                    GameCallback::QuestFinished { quest_id, success: false, .. } =>
+                        *metrics.quests_failed.entry(quest_id.to_string()).or_insert(0) += 1,
+                    GameCallback::NewHero { .. } => metrics.heroes_recruited += 1,
+                    GameCallback::HeroDied { .. } => metrics.hero_deaths += 1,
+                    GameCallback::HeroLeveled { hero_number, skill, new_skill_value } => {
+                        let old = skills.get(*hero_number).and_then(|e|e.get(skill)).copied().unwrap_or(*new_skill_value);
+                        *metrics.skill_growth.entry(*skill).or_insert(0.0) += new_skill_value - old;
+                        if let Some(hero) = skills.get_mut(*hero_number) {
+                            hero.insert(*skill, *new_skill_value);
+                        }
+                    },
+                    _ => {}
+                }
+            }
+            skills = hero_skills(game);
+            metrics.sample(game, config.sample_every);
+        });
+        game
+    }));
+
+    match result {
+        Ok(game) => {
+            let state = game.get_state();
+            metrics.turns = state.turn;
+            state.tiles.iter()
+                .filter_map(|e|e.content.as_ref())
+                .for_each(|e|*metrics.buildings.entry(e.to_string()).or_insert(0) += 1);
+        },
+        Err(_) => {
+            metrics.crashed = true;
+            metrics.panic = LAST_PANIC.with(|e|e.borrow_mut().take());
+        },
+    }
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_panic() {
+        panic::set_hook(Box::new(record_panic));
+        let result = panic::catch_unwind(||panic!("broken resources"));
+        drop(panic::take_hook());
+
+        assert!(result.is_err());
+        let recorded = LAST_PANIC.with(|e|e.borrow_mut().take()).unwrap();
+        assert!(recorded.starts_with("broken resources at "), "{}", recorded);
+        assert!(recorded.contains("metrics.rs"), "{}", recorded);
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::metrics::GameMetrics;
+
+pub const CSV_HEADER: &str = "metric,count,mean,min,p10,p25,p50,p75,p90,max";
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Stats{
+    pub count: usize,
+    pub mean: f64,
+    pub min: f64,
+    pub p10: f64,
+    pub p25: f64,
+    pub p50: f64,
+    pub p75: f64,
+    pub p90: f64,
+    pub max: f64
+}
+
+/// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[f64], p: f64) -> f64{
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+impl Stats {
+    pub fn new(mut values: Vec<f64>) -> Option<Stats>{
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(|a, b|a.total_cmp(b));
+        Some(Stats {
+            count: values.len(),
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            min: values[0],
+            p10: percentile(&values, 10.0),
+            p25: percentile(&values, 25.0),
+            p50: percentile(&values, 50.0),
+            p75: percentile(&values, 75.0),
+            p90: percentile(&values, 90.0),
+            max: values[values.len() - 1]
+        })
+    }
+
+    pub fn to_csv_row(&self, metric: &str) -> String{
+        format!("{},{},{},{},{},{},{},{},{},{}", metric, self.count, self.mean, self.min, self.p10, self.p25, self.p50, self.p75, self.p90, self.max)
+    }
+}
+
+fn add(res: &mut BTreeMap<String, Stats>, metric: String, values: Vec<f64>){
+    if let Some(stats) = Stats::new(values) {
+        res.insert(metric, stats);
+    }
+}
+
+/// Percentiles of every metric over all games. Counters missing in a game
+/// count as 0, turns of unreached tiers are left out (see `count`).
+pub fn aggregate(games: &[GameMetrics]) -> BTreeMap<String, Stats>{
+    let mut res = BTreeMap::new();
+    let finished: Vec<&GameMetrics> = games.iter().filter(|e|!e.crashed).collect();
+    let counter = |map: fn(&GameMetrics) -> &BTreeMap<String, u32>, key: &str|
+        finished.iter().map(|e|*map(e).get(key).unwrap_or(&0) as f64).collect::<Vec<_>>();
+
+    add(&mut res, "crashed".to_string(), games.iter().map(|e|if e.crashed { 1.0 } else { 0.0 }).collect());
+    add(&mut res, "turns".to_string(), finished.iter().map(|e|e.turns as f64).collect());
+    add(&mut res, "heroes.recruited".to_string(), finished.iter().map(|e|e.heroes_recruited as f64).collect());
+    add(&mut res, "heroes.died".to_string(), finished.iter().map(|e|e.hero_deaths as f64).collect());
+
+    let tiers: BTreeSet<u32> = finished.iter().flat_map(|e|e.tier_turns.keys().copied()).collect();
+    for tier in tiers {
+        add(&mut res, format!("win_path.level_{}.turn", tier), finished.iter().filter_map(|e|e.tier_turns.get(&tier).map(|t|*t as f64)).collect());
+    }
+
+    let samples: BTreeSet<(u32, _)> = finished.iter()
+        .flat_map(|e|e.resources.iter().flat_map(|s|s.1.keys().map(|r|(*s.0, *r))))
+        .collect();
+    for (turn, resource) in samples {
+        add(&mut res, format!("resources.{:?}.turn_{}", resource, turn),
+            finished.iter().filter_map(|e|e.resources.get(&turn).map(|s|*s.get(&resource).unwrap_or(&0) as f64)).collect());
+    }
+
+    let buildings: BTreeSet<&String> = finished.iter().flat_map(|e|e.buildings.keys()).collect();
+    for id in buildings {
+        add(&mut res, format!("buildings.{}", id), counter(|e|&e.buildings, id));
+    }
+
+    let quests: BTreeSet<&String> = finished.iter()
+        .flat_map(|e|e.quests_spawned.keys().chain(e.quests_succeeded.keys()).chain(e.quests_failed.keys()))
+        .collect();
+    for id in quests {
+        add(&mut res, format!("quests.{}.spawned", id), counter(|e|&e.quests_spawned, id));
+        add(&mut res, format!("quests.{}.succeeded", id), counter(|e|&e.quests_succeeded, id));
+        add(&mut res, format!("quests.{}.failed", id), counter(|e|&e.quests_failed, id));
+        add(&mut res, format!("quests.{}.success_rate", id), finished.iter()
+            .filter_map(|e|{
+                let succeeded = *e.quests_succeeded.get(id).unwrap_or(&0) as f64;
+                let finished = succeeded + *e.quests_failed.get(id).unwrap_or(&0) as f64;
+                (finished > 0.0).then_some(succeeded / finished)
+            })
+            .collect());
+    }
+
+    let skills: BTreeSet<_> = finished.iter().flat_map(|e|e.skill_growth.keys().copied()).collect();
+    for skill in skills {
+        add(&mut res, format!("skill_growth.{:?}", skill), finished.iter().map(|e|*e.skill_growth.get(&skill).unwrap_or(&0.0) as f64).collect());
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::new((1..=10).rev().map(|e|e as f64).collect()).unwrap();
+        assert_eq!(stats.count, 10);
+        assert_eq!(stats.mean, 5.5);
+        assert_eq!((stats.min, stats.max), (1.0, 10.0));
+        assert_eq!((stats.p10, stats.p25, stats.p50, stats.p75, stats.p90), (1.0, 3.0, 5.0, 8.0, 9.0));
+        assert_eq!(Stats::new(vec![]), None);
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let games = vec![
+            GameMetrics { turns: 10, buildings: BTreeMap::from([("cottage".to_string(), 2)]), tier_turns: BTreeMap::from([(0, 0)]), ..Default::default() },
+            GameMetrics { turns: 20, tier_turns: BTreeMap::from([(0, 0), (1, 15)]), ..Default::default() },
+            GameMetrics { crashed: true, ..Default::default() }
+        ];
+        let res = aggregate(&games);
+        assert_eq!(res["crashed"].mean, 1.0 / 3.0);
+        assert_eq!(res["turns"].count, 2);
+        assert_eq!(res["buildings.cottage"].mean, 1.0);
+        assert_eq!(res["win_path.level_1.turn"].count, 1);
+    }
+}
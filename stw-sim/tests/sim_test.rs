@@ -0,0 +1,40 @@
+use std::process::Command;
+
+use serde_json::Value;
+
+fn run_sim(args: &[&str]) -> String{
+    let output = Command::new(env!("CARGO_BIN_EXE_stw-sim"))
+        .args(["--games", "6", "--turns", "20", "--size", "37", "--threads", "3", "--seed", "test seed"])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn sim_reports_json_metrics() {
+    let res: Value = serde_json::from_str(&run_sim(&["--strategy", "quest", "--per-game"])).unwrap();
+    assert_eq!(res["games"], 6);
+    assert_eq!(res["metrics"]["crashed"]["max"], 0.0);
+    assert_eq!(res["metrics"]["turns"]["p50"], 20.0);
+    // the map starts with the village in the middle
+    assert_eq!(res["metrics"]["win_path.level_0.turn"]["max"], 0.0);
+    assert_eq!(res["metrics"]["buildings.village_small"]["min"], 1.0);
+    assert!(res["metrics"]["resources.Gold.turn_20"].is_object());
+    assert_eq!(res["per_game"].as_array().unwrap().len(), 6);
+    assert_eq!(res["per_game"][0]["seed"], "test seed-0");
+}
+
+#[test]
+fn sim_is_deterministic_across_threads() {
+    let csv = run_sim(&["--format", "csv", "--strategy", "random"]);
+    assert!(csv.starts_with("metric,count,mean,min,p10,p25,p50,p75,p90,max\n"));
+    assert!(csv.lines().any(|e|e.starts_with("turns,6,")));
+
+    let single = Command::new(env!("CARGO_BIN_EXE_stw-sim"))
+        .args(["--games", "6", "--turns", "20", "--size", "37", "--threads", "1", "--seed", "test seed", "--format", "csv", "--strategy", "random"])
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8(single.stdout).unwrap(), csv);
+}
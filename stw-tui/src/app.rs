@@ -0,0 +1,215 @@
+use std::collections::HashSet;
+
+use ratatui::crossterm::event::KeyCode;
+use stw_game_lib::game::{STWGame, map::TilePos, game_move::{GameMove, PossibleBuilding}, game_controller::GameCallback};
+
+const LOG_LENGTH: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Panel{
+    Actions,
+    Decisions
+}
+
+pub struct App{
+    pub game: STWGame,
+    pub cursor: TilePos,
+    pub selected_hero: usize,
+    pub panel: Panel,
+    pub log: Vec<String>,
+    pub message: Option<String>,
+    pub quit: bool
+}
+
+impl App {
+    pub fn new(game: STWGame) -> App{
+        App {
+            cursor: game.get_map().get_mid_position(),
+            game,
+            selected_hero: 0,
+            panel: Panel::Actions,
+            log: vec![],
+            message: None,
+            quit: false
+        }
+    }
+
+    pub fn is_playing_history(&self) -> bool{
+        self.game.get_history().is_some()
+    }
+
+    pub fn get_possible_buildings(&self) -> Vec<PossibleBuilding>{
+        self.game.get_possible_building_at_position(&self.cursor)
+    }
+
+    pub fn get_actions_at_cursor(&self) -> Vec<String>{
+        self.game.get_possible_hero_moves()
+            .into_iter()
+            .filter(|e|e.0 == self.cursor)
+            .map(|e|e.1)
+            .collect()
+    }
+
+    /// Tiles the hero can go to next - highlighted in history mode.
+    pub fn get_hero_move_tiles(&self) -> HashSet<TilePos>{
+        self.game.get_possible_hero_moves()
+            .into_iter()
+            .map(|e|e.0)
+            .collect()
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode){
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Left | KeyCode::Char('h') => self.move_cursor(-1, 0),
+            KeyCode::Right | KeyCode::Char('l') => self.move_cursor(1, 0),
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(0, -1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(0, 1),
+            KeyCode::Tab => {
+                let heroes = self.game.get_heroes().len().max(1);
+                self.selected_hero = (self.selected_hero + 1) % heroes;
+            },
+            KeyCode::Char('d') => {
+                self.panel = match self.panel {
+                    Panel::Actions => Panel::Decisions,
+                    Panel::Decisions => Panel::Actions,
+                };
+            },
+            KeyCode::Char('w') => self.perform(GameMove::Wait),
+            KeyCode::Char('s') => self.perform(GameMove::StartHistory(self.cursor, self.selected_hero)),
+            KeyCode::Char('c') => self.perform(GameMove::CancelConstruction(self.cursor)),
+            KeyCode::Char('x') => self.perform(GameMove::Demolish(self.cursor)),
+            KeyCode::Char(c @ '1'..='9') => self.choose(c as usize - '1' as usize),
+            _ => {}
+        }
+    }
+
+    fn move_cursor(&mut self, q: i32, r: i32){
+        let moved = self.cursor.moved(q, r);
+        let in_grid = self.game.get_map().get_rows()
+            .iter()
+            .any(|row|row.iter().any(|e|e.0 == moved));
+        if in_grid {
+            self.cursor = moved;
+        }
+    }
+
+    /// Picks the n-th entry of the side panel.
+    fn choose(&mut self, index: usize){
+        let game_move = if !self.is_playing_history() {
+            self.get_possible_buildings()
+                .get(index)
+                .map(|e|GameMove::Build(self.cursor, e.id.to_string()))
+        } else if self.panel == Panel::Actions {
+            self.get_actions_at_cursor()
+                .get(index)
+                .map(|e|GameMove::PlayMove(self.cursor, e.to_string()))
+        } else {
+            self.game.get_possible_decisions()
+                .get(index)
+                .map(|e|GameMove::MakeDecision(e.to_string()))
+        };
+        if let Some(game_move) = game_move {
+            self.perform(game_move);
+        }
+    }
+
+    fn perform(&mut self, game_move: GameMove){
+        match self.game.perform_move(&game_move) {
+            Ok(callbacks) => {
+                self.message = None;
+                self.log.extend(callbacks.iter().map(describe_callback));
+                let overflow = self.log.len().saturating_sub(LOG_LENGTH);
+                self.log.drain(..overflow);
+                // `HeroDied` shifts the heroes after the dead one
+                self.selected_hero = self.selected_hero.min(self.game.get_heroes().len().saturating_sub(1));
+                if !self.is_playing_history() {
+                    self.panel = Panel::Actions;
+                }
+            },
+            Err(e) => self.message = Some(e.to_string()),
+        }
+    }
+}
+
+pub fn describe_callback(callback: &GameCallback) -> String{
+    match callback {
+        GameCallback::ChangedResource { resource, new_value } => format!("{:?} is now {}", resource, new_value),
+        GameCallback::NewTileContent { position, field_type_id } => format!("Built {} at ({},{})", field_type_id, position.q, position.r),
+        GameCallback::MaxHeroesIncreased { current_max_heroes } => format!("Up to {} heroes", current_max_heroes),
+        GameCallback::MaxHeroesDecreased { current_max_heroes } => format!("Up to {} heroes", current_max_heroes),
+        GameCallback::NewHero { where_born, hero_id } => format!("New hero {} from ({},{})", hero_id, where_born.q, where_born.r),
+        GameCallback::NewQuest { where_created, quest_id } => format!("New quest {} at ({},{})", quest_id, where_created.q, where_created.r),
+        GameCallback::StartedHistory { quest_pos, choosen_hero } => format!("Hero {} goes to quest at ({},{})", choosen_hero, quest_pos.q, quest_pos.r),
+        GameCallback::HeroLeveled { hero_number, skill, new_skill_value } => format!("Hero {} {:?} is now {:.2}", hero_number, skill, new_skill_value),
+        GameCallback::HeroMoved { dest_position, hero_number, success, action_performed } =>
+            format!("Hero {} did {} at ({},{}) - roll {:.2}", hero_number, action_performed, dest_position.q, dest_position.r, success),
+        GameCallback::HeroRenamed { hero_number, name } => format!("Hero {} is now called {}", hero_number, name),
+        GameCallback::HeroDied { hero_number } => format!("Hero {} died", hero_number),
+        GameCallback::QuestFinished { quest_pos, quest_id, decision, success } =>
+            format!("Quest {} at ({},{}) - {}: {}", quest_id, quest_pos.q, quest_pos.r, decision, if *success { "success" } else { "failure" }),
+        GameCallback::TileContentRemoved { position } => format!("Tile ({},{}) destroyed", position.q, position.r),
+        GameCallback::TileDiscovered { position } => format!("Tile ({},{}) discovered", position.q, position.r),
+        GameCallback::BuildingActiveChanged { position, active } =>
+            format!("Building at ({},{}) is {}", position.q, position.r, if *active { "active again" } else { "inactive - upkeep not paid" }),
+        GameCallback::ConstructionProgress { position, field_type_id, turns_left } =>
+            format!("{} at ({},{}) ready in {} turns", field_type_id, position.q, position.r, turns_left),
+        GameCallback::ConstructionFinished { position, field_type_id } => format!("Finished {} at ({},{})", field_type_id, position.q, position.r),
+        GameCallback::ConstructionCancelled { position, field_type_id } => format!("Cancelled {} at ({},{})", field_type_id, position.q, position.r),
+        GameCallback::BuildingDowngraded { position, field_type_id } => format!("Tile ({},{}) is {} again", position.q, position.r, field_type_id),
+        GameCallback::GameWon { condition, score } => format!("Game won ({:?}) - score {}", condition, score),
+        GameCallback::GameLost { condition, score } => format!("Game lost ({:?}) - score {}", condition, score),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::KeyCode;
+    use stw_game_lib::{game::map::TilePos, resource::{load_resources_from_dir, enums::GResource}, GameConfig};
+
+    use super::App;
+
+    fn prepare_app() -> App{
+        let resources = load_resources_from_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/../stw-game-lib/res")).unwrap();
+        let game = GameConfig::new()
+            .set_resources(resources)
+            .set_seed("test seed")
+            .set_map_size(37)
+            .start_game()
+            .unwrap();
+        App::new(game)
+    }
+
+    #[test]
+    fn test_cursor_stays_in_grid() {
+        let mut app = prepare_app();
+        assert_eq!(app.cursor, TilePos { q: 9, r: 18 });
+        app.handle_key(KeyCode::Left);
+        app.handle_key(KeyCode::Char('k'));
+        assert_eq!(app.cursor, TilePos { q: 8, r: 17 });
+        (0..100).for_each(|_|app.handle_key(KeyCode::Up));
+        assert_eq!(app.cursor, TilePos { q: 8, r: 0 });
+    }
+
+    #[test]
+    fn test_build_from_panel() {
+        let mut app = prepare_app();
+        app.handle_key(KeyCode::Left);
+        assert_eq!(app.get_possible_buildings()[0].id, "cottage");
+        app.handle_key(KeyCode::Char('1'));
+        assert_eq!(app.game.get_resources()[&GResource::Gold], 3);
+        assert_eq!(app.log.last().unwrap(), "Built cottage at (8,18)");
+
+        app.handle_key(KeyCode::Char('s'));
+        assert!(app.message.is_some());
+    }
+
+    #[test]
+    fn test_selected_hero_is_clamped() {
+        let mut app = prepare_app();
+        app.selected_hero = 3;
+        app.handle_key(KeyCode::Char('w'));
+        assert_eq!(app.game.get_heroes().len(), 1);
+        assert_eq!(app.selected_hero, 0);
+    }
+}
@@ -0,0 +1,77 @@
+mod app;
+mod ui;
+
+use std::{env, io, process};
+
+use app::App;
+use ratatui::{DefaultTerminal, crossterm::event::{self, Event, KeyEventKind}};
+use stw_game_lib::{resource::load_resources_from_dir, GameConfig};
+
+const USAGE: &str = "Usage: stw-tui [--seed SEED] [--size SIZE] [--resources DIR]";
+
+struct Args{
+    seed: Option<String>,
+    size: Option<u32>,
+    resources: String
+}
+
+fn parse_args() -> Result<Args, String>{
+    let mut res = Args {
+        seed: None,
+        size: None,
+        resources: concat!(env!("CARGO_MANIFEST_DIR"), "/../stw-game-lib/res").to_string()
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = ||args.next().ok_or_else(||format!("Missing value for {}", arg));
+        match arg.as_str() {
+            "--seed" => res.seed = Some(value()?),
+            "--size" => res.size = Some(value()?.parse().map_err(|_|"Size has to be a number".to_string())?),
+            "--resources" => res.resources = value()?,
+            other => return Err(format!("Unknown argument {}", other)),
+        }
+    }
+    Ok(res)
+}
+
+fn run(terminal: &mut DefaultTerminal, mut app: App) -> io::Result<()>{
+    while !app.quit {
+        terminal.draw(|frame|ui::draw(frame, &app))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                app.handle_key(key.code);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    let args = parse_args().unwrap_or_else(|e|{
+        eprintln!("{}\n{}", e, USAGE);
+        process::exit(2);
+    });
+    let game = load_resources_from_dir(&args.resources)
+        .and_then(|resources|{
+            let mut config = GameConfig::new().set_resources(resources);
+            if let Some(seed) = &args.seed {
+                config = config.set_seed(seed);
+            }
+            if let Some(size) = args.size {
+                config = config.set_map_size(size);
+            }
+            config.start_game()
+        })
+        .unwrap_or_else(|e|{
+            eprintln!("{}", e);
+            process::exit(1);
+        });
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, App::new(game));
+    ratatui::restore();
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,189 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, Paragraph}
+};
+
+use crate::app::{App, Panel};
+
+const CELL_WIDTH: u16 = 2;
+const HELP: &str = "arrows/hjkl move  1-9 choose  w wait  s start quest  c cancel construction  x demolish  tab hero  d actions/decisions  q quit";
+
+pub fn draw(frame: &mut Frame, app: &App){
+    let [top, middle, log, bottom] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(10),
+        Constraint::Length(8),
+        Constraint::Length(1)
+    ]).areas(frame.area());
+    let [map, side] = Layout::horizontal([Constraint::Min(20), Constraint::Length(40)]).areas(middle);
+    let [panel, heroes, quests] = Layout::vertical([
+        Constraint::Min(6),
+        Constraint::Length(7),
+        Constraint::Length(7)
+    ]).areas(side);
+
+    draw_resources(frame, app, top);
+    draw_map(frame, app, map);
+    draw_panel(frame, app, panel);
+    draw_heroes(frame, app, heroes);
+    draw_quests(frame, app, quests);
+    draw_log(frame, app, log);
+
+    let status = match &app.message {
+        Some(msg) => Line::styled(msg.to_string(), Style::new().fg(Color::Red)),
+        None => Line::raw(HELP),
+    };
+    frame.render_widget(Paragraph::new(status), bottom);
+}
+
+fn draw_resources(frame: &mut Frame, app: &App, area: Rect){
+    let mut resources: Vec<_> = app.game.get_resources().iter().collect();
+    resources.sort();
+    let mut spans = vec![Span::styled(format!("Turn {}", app.game.get_turn()), Style::new().add_modifier(Modifier::BOLD))];
+    spans.extend(resources.iter().map(|e|Span::raw(format!("  {:?}: {}", e.0, e.1))));
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// First visible index so that `cursor` stays in the middle of `visible` cells
+/// when possible.
+fn scroll_offset(cursor: usize, visible: usize, total: usize) -> usize{
+    cursor.saturating_sub(visible / 2).min(total.saturating_sub(visible))
+}
+
+fn draw_map(frame: &mut Frame, app: &App, area: Rect){
+    let block = Block::bordered().title(format!(" Map ({},{}) ", app.cursor.q, app.cursor.r));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let rows = app.game.get_map().get_rows();
+    let hero_moves = app.get_hero_move_tiles();
+    let quests = app.game.get_quests();
+    let (cursor_row, cursor_col) = rows.iter()
+        .enumerate()
+        .find_map(|(r, row)|row.iter().position(|e|e.0 == app.cursor).map(|c|(r, c)))
+        .unwrap_or_default();
+    let visible_cols = (inner.width / CELL_WIDTH) as usize;
+    let visible_rows = inner.height as usize;
+    let row_offset = scroll_offset(cursor_row, visible_rows, rows.len());
+    let col_offset = scroll_offset(cursor_col, visible_cols, rows.first().map_or(0, |e|e.len()));
+
+    let lines: Vec<Line> = rows.iter()
+        .skip(row_offset)
+        .take(visible_rows)
+        .map(|row|Line::from(row.iter()
+            .skip(col_offset)
+            .take(visible_cols)
+            .map(|(pos, tile)|{
+                let Some(tile) = tile else {
+                    return Span::raw("  ");
+                };
+                let (r, g, b) = tile.get_color();
+                let mut style = Style::new().fg(Color::Rgb(r, g, b));
+                if hero_moves.contains(pos) {
+                    style = style.bg(Color::Blue);
+                } else if quests.contains_key(pos) {
+                    style = style.bg(Color::Rgb(96, 0, 0));
+                }
+                if *pos == app.cursor {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                Span::styled(tile.get_short_name(), style)
+            })
+            .collect::<Vec<_>>()
+        ))
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn draw_panel(frame: &mut Frame, app: &App, area: Rect){
+    let (title, entries) = match (app.game.get_history(), app.panel) {
+        (None, _) => (
+            " Build ".to_string(),
+            app.get_possible_buildings().into_iter()
+                .map(|e|match e.not_enought_resources {
+                    true => (e.id, Style::new().fg(Color::DarkGray)),
+                    false => (e.id, Style::new()),
+                })
+                .collect::<Vec<_>>()
+        ),
+        (Some(history), Panel::Actions) => (
+            format!(" Actions ({} steps left) ", history.path_left),
+            app.get_actions_at_cursor().into_iter().map(|e|(e, Style::new())).collect()
+        ),
+        (Some(_), Panel::Decisions) => (
+            " Decisions ".to_string(),
+            app.game.get_possible_decisions().into_iter().map(|e|(e, Style::new())).collect()
+        ),
+    };
+    let items: Vec<ListItem> = entries.into_iter()
+        .enumerate()
+        .map(|(i, (text, style))|ListItem::new(format!("{} {}", i + 1, text)).style(style))
+        .collect();
+    frame.render_widget(List::new(items).block(Block::bordered().title(title)), area);
+}
+
+fn draw_heroes(frame: &mut Frame, app: &App, area: Rect){
+    let on_quest = app.game.get_history().map(|e|e.hero_index);
+    let items: Vec<ListItem> = app.game.get_heroes().iter()
+        .enumerate()
+        .map(|(i, hero)|{
+            let mut style = Style::new();
+            if i == app.selected_hero {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            if on_quest == Some(i) {
+                style = style.fg(Color::Yellow);
+            }
+            ListItem::new(format!("{} {} ({})", i, hero.get_name(), hero.get_background().data.hero_family)).style(style)
+        })
+        .collect();
+    frame.render_widget(List::new(items).block(Block::bordered().title(" Heroes ")), area);
+}
+
+fn draw_quests(frame: &mut Frame, app: &App, area: Rect){
+    let mut quests: Vec<_> = app.game.get_quests().iter().collect();
+    quests.sort_by_key(|e|e.0);
+    let items: Vec<ListItem> = quests.iter()
+        .map(|(pos, quest)|ListItem::new(format!("({},{}) {}", pos.q, pos.r, quest.get_quest_type().id)))
+        .collect();
+    frame.render_widget(List::new(items).block(Block::bordered().title(" Quests ")), area);
+}
+
+fn draw_log(frame: &mut Frame, app: &App, area: Rect){
+    let visible = area.height.saturating_sub(2) as usize;
+    let lines: Vec<Line> = app.log.iter()
+        .skip(app.log.len().saturating_sub(visible))
+        .map(|e|Line::raw(e.to_string()))
+        .collect();
+    frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(" Log ")), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{Terminal, backend::TestBackend};
+    use stw_game_lib::{resource::load_resources_from_dir, GameConfig};
+
+    use crate::app::App;
+
+    #[test]
+    fn test_draw() {
+        let resources = load_resources_from_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/../stw-game-lib/res")).unwrap();
+        let game = GameConfig::new()
+            .set_resources(resources)
+            .set_seed("test seed")
+            .set_map_size(37)
+            .start_game()
+            .unwrap();
+        let app = App::new(game);
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|frame|super::draw(frame, &app)).unwrap();
+        let text: String = terminal.backend().buffer().content().iter().map(|e|e.symbol()).collect();
+        assert!(text.contains("Turn 0"));
+        assert!(text.contains("Map (9,18)"));
+        assert!(text.contains("1 "));
+    }
+}